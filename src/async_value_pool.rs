@@ -0,0 +1,261 @@
+//! This module implements [`AsyncValuePool<T>`], a [`ValuePool<T>`] that can be shared between tasks of an async executor without blocking threads.
+//!
+//! Only available with the `async` feature. It doesn't depend on a specific executor.
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug)]
+struct State<T> {
+    // `None` while a guard holds the pool
+    pool: Option<ValuePool<T>>,
+    waiters: VecDeque<Waker>,
+}
+
+/// An [`AsyncValuePool<T>`] is a cheaply clonable handle to a shared [`ValuePool<T>`].
+/// Waiting for access suspends the task instead of blocking the thread.
+/// The returned guards own their access, so they can be held across `.await` points and moved into other tasks.
+/// ```
+/// # use std::{future::Future, pin::pin, sync::Arc, task::{Context, Poll, Wake, Waker}};
+/// # struct NoopWaker;
+/// # impl Wake for NoopWaker { fn wake(self: Arc<Self>) {} }
+/// # fn block_on<F: Future>(future: F) -> F::Output {
+/// #     let waker = Waker::from(Arc::new(NoopWaker));
+/// #     let mut future = pin!(future);
+/// #     loop {
+/// #         if let Poll::Ready(res) = future.as_mut().poll(&mut Context::from_waker(&waker)) { return res; }
+/// #     }
+/// # }
+/// use value_pool::async_value_pool::AsyncValuePool;
+///
+/// let pool: AsyncValuePool<String> = AsyncValuePool::new();
+/// block_on(async {
+///     let reference = pool.push("hello".to_string()).await;
+///
+///     let mut value = pool.get_owned(reference).await.unwrap();
+///     value.push_str(" world");
+///     assert!(pool.try_lock().is_none()); // `value` still holds the pool
+///     drop(value);
+///
+///     assert_eq!(pool.take(reference).await, Some("hello world".to_string()));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct AsyncValuePool<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Clone for AsyncValuePool<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        AsyncValuePool {
+            shared: (Arc::clone(&self.shared)),
+        }
+    }
+}
+
+impl<T> Default for AsyncValuePool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<ValuePool<T>> for AsyncValuePool<T> {
+    #[inline]
+    fn from(pool: ValuePool<T>) -> Self {
+        AsyncValuePool {
+            shared: (Arc::new(Mutex::new(State {
+                pool: Some(pool),
+                waiters: (VecDeque::new()),
+            }))),
+        }
+    }
+}
+
+impl<T> AsyncValuePool<T> {
+    /// Creates a new, empty [`AsyncValuePool`].
+    #[inline]
+    pub fn new() -> AsyncValuePool<T> {
+        ValuePool::new().into()
+    }
+
+    #[inline]
+    fn poll_lock(&self, cx: Option<&mut Context<'_>>) -> Poll<AsyncPoolGuard<T>> {
+        let mut state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        match state.pool.take() {
+            Some(pool) => Poll::Ready(AsyncPoolGuard {
+                shared: (Arc::clone(&self.shared)),
+                pool: Some(pool),
+            }),
+            None => {
+                if let Some(cx) = cx {
+                    if !state.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                        state.waiters.push_back(cx.waker().clone());
+                    }
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Waits until no other guard holds the pool and returns a guard with exclusive access to the whole [`ValuePool<T>`].
+    #[inline]
+    pub async fn lock(&self) -> AsyncPoolGuard<T> {
+        poll_fn(|cx| self.poll_lock(Some(cx))).await
+    }
+
+    /// Returns a guard with exclusive access to the whole [`ValuePool<T>`] if no other guard holds it.
+    #[inline]
+    pub fn try_lock(&self) -> Option<AsyncPoolGuard<T>> {
+        match self.poll_lock(None) {
+            Poll::Ready(guard) => Some(guard),
+            Poll::Pending => None,
+        }
+    }
+
+    /// Waits for access and returns a guard to the value at `reference`. Returns [`None`] if no value is stored there.
+    /// The whole pool stays locked until the guard is dropped.
+    #[inline]
+    pub async fn get_owned(&self, reference: impl Into<ValueRef<T>>) -> Option<OwnedGuard<T>> {
+        let reference: ValueRef<T> = reference.into();
+        let guard = self.lock().await;
+        if !guard.has_item(reference) {
+            return None;
+        }
+        Some(OwnedGuard { guard, reference })
+    }
+
+    /// Same as [`ValuePool<T>::push`], but waits for access first.
+    #[inline]
+    pub async fn push(&self, value: T) -> ValueRef<T> {
+        self.lock().await.push(value)
+    }
+
+    /// Same as [`ValuePool<T>::take`], but waits for access first.
+    #[inline]
+    pub async fn take(&self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        self.lock().await.take(reference)
+    }
+}
+
+/// Exclusive access to the [`ValuePool<T>`] of an [`AsyncValuePool<T>`]. The pool is released on drop.
+#[derive(Debug)]
+pub struct AsyncPoolGuard<T> {
+    shared: Arc<Mutex<State<T>>>,
+    pool: Option<ValuePool<T>>,
+}
+
+impl<T> Deref for AsyncPoolGuard<T> {
+    type Target = ValuePool<T>;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.pool
+            .as_ref()
+            .expect("Guard to hold the pool until dropped")
+    }
+}
+
+impl<T> DerefMut for AsyncPoolGuard<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pool
+            .as_mut()
+            .expect("Guard to hold the pool until dropped")
+    }
+}
+
+impl<T> Drop for AsyncPoolGuard<T> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            state.pool = self.pool.take();
+            std::mem::take(&mut state.waiters)
+        };
+        // Waking everyone is simple and can't lose a wakeup if a waiting future got dropped
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
+
+/// Exclusive access to one value of an [`AsyncValuePool<T>`]. The pool is released on drop.
+#[derive(Debug)]
+pub struct OwnedGuard<T> {
+    guard: AsyncPoolGuard<T>,
+    reference: ValueRef<T>,
+}
+
+impl<T> OwnedGuard<T> {
+    /// Returns the position of the guarded value.
+    #[inline]
+    pub fn reference(&self) -> ValueRef<T> {
+        self.reference
+    }
+}
+
+impl<T> Deref for OwnedGuard<T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.guard
+            .get(self.reference)
+            .expect("Value to exist while the guard holds the pool")
+    }
+}
+
+impl<T> DerefMut for OwnedGuard<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard
+            .get_mut(self.reference)
+            .expect("Value to exist while the guard holds the pool")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncValuePool;
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    struct CountingWaker(AtomicUsize);
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_waiting_task_gets_woken() {
+        let pool: AsyncValuePool<u32> = AsyncValuePool::new();
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+
+        let guard = pool.try_lock().unwrap();
+        let mut waiting = pin!(pool.push(3));
+        assert!(waiting.as_mut().poll(&mut cx).is_pending());
+        assert!(waiting.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+        drop(guard);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        let Poll::Ready(reference) = waiting.as_mut().poll(&mut cx) else {
+            panic!("Expected access after the guard got dropped");
+        };
+        assert_eq!(pool.try_lock().unwrap().get(reference), Some(&3));
+    }
+}
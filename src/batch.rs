@@ -0,0 +1,110 @@
+//! This module implements [`ValuePool::apply`], which executes a batch of [`PoolOp<T>`]s, e.g. from a command buffer.
+use crate::{ValuePool, ValueRef};
+
+/// One operation of a batch executed by [`ValuePool::apply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolOp<T> {
+    /// [`ValuePool::push`] the value.
+    Push(T),
+    /// [`ValuePool::take`] the value at the position.
+    Take(ValueRef<T>),
+    /// Replace the value at the position, if one is stored there.
+    Replace(ValueRef<T>, T),
+    /// [`ValuePool::remove`] the value at the position.
+    Remove(ValueRef<T>),
+}
+
+/// The result of one [`PoolOp<T>`], returned by [`ValuePool::apply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpResult<T> {
+    /// The position of the pushed value.
+    Pushed(ValueRef<T>),
+    /// The taken value, if one was stored.
+    Taken(Option<T>),
+    /// `Ok(old value)`, or `Err(value)` if no value was stored at the position.
+    Replaced(Result<T, T>),
+    /// The removed value, if one was stored.
+    Removed(Option<T>),
+}
+
+impl<T> ValuePool<T> {
+    /// Executes all `ops` and returns their results in the same order.
+    ///
+    /// All [`PoolOp::Take`], [`PoolOp::Replace`] and [`PoolOp::Remove`] operations run first, in their given order,
+    /// followed by all [`PoolOp::Push`]es. This way pushes reuse the positions freed by the same batch.
+    /// Note: A push therefore never influences another operation of its batch.
+    /// ```
+    /// use value_pool::{ValuePool, batch::{OpResult, PoolOp}};
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let a = pool.push("a");
+    /// let b = pool.push("b");
+    ///
+    /// let results = pool.apply([PoolOp::Push("c"), PoolOp::Take(a), PoolOp::Replace(b, "B")]);
+    /// assert_eq!(results, vec![OpResult::Pushed(a), OpResult::Taken(Some("a")), OpResult::Replaced(Ok("b"))]);
+    /// assert_eq!(pool.get(a), Some(&"c")); // the push reused the position of `a`
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the number of operations
+    pub fn apply(&mut self, ops: impl IntoIterator<Item = PoolOp<T>>) -> Vec<OpResult<T>> {
+        let mut results: Vec<Option<OpResult<T>>> = Vec::new();
+        let mut pushes = Vec::new();
+        for op in ops {
+            let result = match op {
+                PoolOp::Push(value) => {
+                    pushes.push((results.len(), value));
+                    None
+                }
+                PoolOp::Take(reference) => Some(OpResult::Taken(self.take(reference))),
+                PoolOp::Replace(reference, value) => {
+                    Some(OpResult::Replaced(match self.get_mut(reference) {
+                        Some(stored) => Ok(std::mem::replace(stored, value)),
+                        None => Err(value),
+                    }))
+                }
+                PoolOp::Remove(reference) => Some(OpResult::Removed(self.remove(reference))),
+            };
+            results.push(result);
+        }
+        for (position, value) in pushes {
+            results[position] = Some(OpResult::Pushed(self.push(value)));
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("Every operation to have a result"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OpResult, PoolOp};
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_failed_ops() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        let first = pool.push(1);
+        let missing = ValueRef::new(5);
+
+        let results = pool.apply([
+            PoolOp::Remove(first),
+            PoolOp::Remove(first),
+            PoolOp::Take(missing),
+            PoolOp::Replace(missing, 2),
+            PoolOp::Push(3),
+        ]);
+        assert_eq!(
+            results,
+            vec![
+                OpResult::Removed(Some(1)),
+                OpResult::Removed(None),
+                OpResult::Taken(None),
+                OpResult::Replaced(Err(2)),
+                OpResult::Pushed(first),
+            ]
+        );
+        assert_eq!(pool.element_count(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+//! This module implements [`CachePadded<T>`], which aligns every slot of a pool to the cache line size to avoid false sharing.
+use std::ops::{Deref, DerefMut};
+
+use crate::ValuePool;
+
+/// [`CachePadded<T>`] pads and aligns `T` to the cache line size (128 bytes on `x86_64` and `aarch64`, 64 bytes otherwise).
+/// Stored in a [`ValuePool`], no two values share a cache line, so threads working on adjacent slots
+/// don't slow each other down through false sharing. This trades memory for speed, use it only for pools that many threads access concurrently.
+/// ```
+/// use value_pool::cache_padded::{CachePadded, PaddedPool};
+///
+/// let mut counters: PaddedPool<u64> = PaddedPool::new();
+/// let first = counters.push(CachePadded::new(0));
+/// let second = counters.push(CachePadded::new(0));
+///
+/// **counters.get_mut(first).unwrap() += 1;
+/// assert_eq!(counters.get(first).map(|counter| **counter), Some(1));
+/// assert_eq!(counters.take(second).map(CachePadded::into_inner), Some(0));
+/// assert!(std::mem::align_of::<CachePadded<u64>>() >= 64);
+/// ```
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(align(128)))]
+#[cfg_attr(
+    not(any(target_arch = "x86_64", target_arch = "aarch64")),
+    repr(align(64))
+)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+/// A [`ValuePool`] whose slots don't share cache lines.
+pub type PaddedPool<T> = ValuePool<CachePadded<T>>;
+
+impl<T> CachePadded<T> {
+    /// Pads `value` to the cache line size.
+    #[inline]
+    pub const fn new(value: T) -> CachePadded<T> {
+        CachePadded { value }
+    }
+
+    /// Returns the padded value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        CachePadded::new(value)
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachePadded, PaddedPool};
+
+    #[test]
+    fn test_slots_on_own_cache_lines() {
+        let mut pool: PaddedPool<u8> = PaddedPool::new();
+        let first = pool.push(1.into());
+        let second = pool.push(2.into());
+
+        let align = std::mem::align_of::<CachePadded<u8>>();
+        let distance = (&**pool.get(second).unwrap() as *const u8 as usize)
+            - (&**pool.get(first).unwrap() as *const u8 as usize);
+        assert!(distance >= align);
+        assert_eq!(std::mem::size_of::<Option<CachePadded<u8>>>() % align, 0);
+    }
+}
@@ -0,0 +1,309 @@
+//! This module implements [`ValuePool::iter_chunks`] and [`ValuePool::iter_chunks_mut`], which iterate over runs of consecutive occupied positions, and [`ValuePool::chunk`].
+use std::{
+    iter::FusedIterator,
+    ops::{Bound, RangeBounds},
+};
+
+use crate::{ValuePool, ValueRef};
+
+/// A run of consecutive occupied positions of a [`ValuePool<T>`], returned by [`ValuePool::iter_chunks`] and [`ValuePool::chunk`].
+/// All of its positions store an item. Only [`ValuePool::chunk`] returns empty chunks, for empty ranges.
+#[derive(Debug)]
+pub struct Chunk<'a, T> {
+    start: usize,
+    slots: &'a [Option<T>],
+}
+
+impl<T> Clone for Chunk<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Chunk<'_, T> {}
+
+impl<'a, T> Chunk<'a, T> {
+    /// Creates a [`Chunk`] from `slots` starting at position `start`. All `slots` must store an item.
+    #[inline]
+    pub(crate) fn new(start: usize, slots: &'a [Option<T>]) -> Chunk<'a, T> {
+        debug_assert!(slots.iter().all(Option::is_some));
+        Chunk { start, slots }
+    }
+
+    /// Returns the position of the first item.
+    #[inline]
+    pub fn start(&self) -> ValueRef<T> {
+        ValueRef::new(self.start)
+    }
+
+    /// Returns the number of items in this [`Chunk`].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if this [`Chunk`] is empty. Chunks returned by [`ValuePool::iter_chunks`] never are.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Gets the `index`th item of this [`Chunk`], not the item at position `index` of the pool.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Iterates over the items of this [`Chunk`].
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator {
+        self.slots
+            .iter()
+            .map(|slot| slot.as_ref().expect("Chunks to only contain items"))
+    }
+
+    /// Iterates over the items of this [`Chunk`] together with their positions.
+    #[inline]
+    pub fn iter_with_refs(&self) -> impl DoubleEndedIterator<Item = (ValueRef<T>, &'a T)> {
+        let start = self.start;
+        self.iter()
+            .enumerate()
+            .map(move |(offset, value)| (ValueRef::new(start + offset), value))
+    }
+}
+
+/// A run of consecutive occupied positions of a [`ValuePool<T>`] with mutable access, returned by [`ValuePool::iter_chunks_mut`].
+#[derive(Debug)]
+pub struct ChunkMut<'a, T> {
+    start: usize,
+    slots: &'a mut [Option<T>],
+}
+
+impl<T> ChunkMut<'_, T> {
+    /// Returns the position of the first item.
+    #[inline]
+    pub fn start(&self) -> ValueRef<T> {
+        ValueRef::new(self.start)
+    }
+
+    /// Returns the number of items in this [`ChunkMut`].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if this [`ChunkMut`] is empty. Chunks returned by [`ValuePool::iter_chunks_mut`] never are.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Gets the `index`th item of this [`ChunkMut`].
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Gets the `index`th item of this [`ChunkMut`] mutably.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)?.as_mut()
+    }
+
+    /// Iterates over the items of this [`ChunkMut`].
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.slots
+            .iter()
+            .map(|slot| slot.as_ref().expect("Chunks to only contain items"))
+    }
+
+    /// Iterates mutably over the items of this [`ChunkMut`].
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
+        self.slots
+            .iter_mut()
+            .map(|slot| slot.as_mut().expect("Chunks to only contain items"))
+    }
+}
+
+/// Iterator over the [`Chunk`]s of a [`ValuePool<T>`], see [`ValuePool::iter_chunks`].
+#[derive(Debug)]
+pub struct Chunks<'a, T> {
+    offset: usize,
+    rest: &'a [Option<T>],
+}
+
+impl<'a, T> Iterator for Chunks<'a, T> {
+    type Item = Chunk<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let skip = self.rest.iter().take_while(|slot| slot.is_none()).count();
+        let rest = &self.rest[skip..];
+        let len = rest.iter().take_while(|slot| slot.is_some()).count();
+        let start = self.offset + skip;
+        let (chunk, rest) = rest.split_at(len);
+        self.offset = start + len;
+        self.rest = rest;
+        (len > 0).then(|| Chunk::new(start, chunk))
+    }
+}
+
+impl<T> FusedIterator for Chunks<'_, T> {}
+
+/// Iterator over the [`ChunkMut`]s of a [`ValuePool<T>`], see [`ValuePool::iter_chunks_mut`].
+#[derive(Debug)]
+pub struct ChunksMut<'a, T> {
+    offset: usize,
+    rest: &'a mut [Option<T>],
+}
+
+impl<'a, T> Iterator for ChunksMut<'a, T> {
+    type Item = ChunkMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = std::mem::take(&mut self.rest);
+        let skip = rest.iter().take_while(|slot| slot.is_none()).count();
+        let rest = &mut rest[skip..];
+        let len = rest.iter().take_while(|slot| slot.is_some()).count();
+        let start = self.offset + skip;
+        let (chunk, rest) = rest.split_at_mut(len);
+        self.offset = start + len;
+        self.rest = rest;
+        (len > 0).then_some(ChunkMut {
+            start,
+            slots: chunk,
+        })
+    }
+}
+
+impl<T> FusedIterator for ChunksMut<'_, T> {}
+
+impl<T> ValuePool<T> {
+    /// Iterates over all runs of consecutive occupied positions, in slot order.
+    /// Processing a whole [`Chunk`] at once is more cache-friendly than looking up every item on its own.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let refs: Vec<_> = (0..6).map(|value| pool.push(value)).collect();
+    /// pool.take(refs[2]);
+    ///
+    /// let sums: Vec<u32> = pool.iter_chunks().map(|chunk| chunk.iter().sum()).collect();
+    /// assert_eq!(sums, vec![0 + 1, 3 + 4 + 5]);
+    /// assert_eq!(pool.iter_chunks().nth(1).unwrap().start(), refs[3]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` for the whole iteration
+    #[inline]
+    pub fn iter_chunks(&self) -> Chunks<'_, T> {
+        Chunks {
+            offset: 0,
+            rest: &self.store,
+        }
+    }
+
+    /// Same as [`ValuePool::iter_chunks`], but gives mutable access to the items.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let first = pool.push(1);
+    /// let second = pool.push(2);
+    /// for mut chunk in pool.iter_chunks_mut() {
+    ///     chunk.iter_mut().for_each(|value| *value *= 10);
+    /// }
+    /// assert_eq!((pool.get(first), pool.get(second)), (Some(&10), Some(&20)));
+    /// ```
+    #[inline]
+    pub fn iter_chunks_mut(&mut self) -> ChunksMut<'_, T> {
+        ChunksMut {
+            offset: 0,
+            rest: &mut self.store,
+        }
+    }
+
+    /// Returns the items between the positions of `range` as one [`Chunk`], or [`None`] if any position in `range` is empty or out of bounds.
+    /// Items pushed one after another without removals in between are stored consecutively, so this gives zero-copy access to them.
+    /// Items are stored as [`Option<T>`], so the [`Chunk`] can't be turned into a plain `&[T]`.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let first = pool.push(1);
+    /// pool.push(2);
+    /// let last = pool.push(3);
+    ///
+    /// let chunk = pool.chunk(first..=last).unwrap();
+    /// assert_eq!(chunk.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert_eq!(pool.chunk(first..last).map(|chunk| chunk.len()), Some(2));
+    ///
+    /// pool.take(last);
+    /// assert!(pool.chunk(first..=last).is_none());
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(k)` where `k` is the length of `range`
+    pub fn chunk(&self, range: impl RangeBounds<ValueRef<T>>) -> Option<Chunk<'_, T>> {
+        let start = match range.start_bound() {
+            Bound::Included(start) => start.index.get(),
+            Bound::Excluded(start) => start.index.get().checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.index.get().checked_add(1)?,
+            Bound::Excluded(end) => end.index.get(),
+            Bound::Unbounded => self.store.len(),
+        };
+        let slots = self.store.get(start..end.max(start))?;
+        slots
+            .iter()
+            .all(Option::is_some)
+            .then(|| Chunk::new(start, slots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        for value in 0..8 {
+            pool.push(value);
+        }
+        for index in [0, 3, 4, 7] {
+            pool.take(ValueRef::new(index));
+        }
+        let chunks: Vec<(usize, Vec<u32>)> = pool
+            .iter_chunks()
+            .map(|chunk| (chunk.start().index.get(), chunk.iter().copied().collect()))
+            .collect();
+        assert_eq!(chunks, vec![(1, vec![1, 2]), (5, vec![5, 6])]);
+
+        let mut chunks = pool.iter_chunks_mut();
+        let mut first = chunks.next().unwrap();
+        *first.get_mut(1).unwrap() = 20;
+        assert_eq!(first.get(2), None);
+        assert_eq!(chunks.count(), 1);
+        assert_eq!(pool.get(ValueRef::new(2)), Some(&20));
+        assert_eq!(ValuePool::<u32>::new().iter_chunks().count(), 0);
+
+        assert_eq!(
+            pool.chunk(ValueRef::new(5)..).map(|chunk| chunk.len()),
+            None
+        );
+        assert_eq!(
+            pool.chunk(ValueRef::new(5)..ValueRef::new(7))
+                .map(|chunk| chunk.len()),
+            Some(2)
+        );
+        assert!(pool
+            .chunk(ValueRef::new(6)..ValueRef::new(6))
+            .unwrap()
+            .is_empty());
+        assert!(pool.chunk(ValueRef::new(8)..=ValueRef::new(9)).is_none());
+    }
+}
@@ -0,0 +1,17 @@
+//! This module groups the types of this crate that are meant to be shared between threads or tasks.
+//! Every module stays available at its old path as well, e.g. [`crate::waitable_pool`].
+//! ```
+//! use value_pool::concurrent::steal_queue::StealQueue;
+//!
+//! let mut queue: StealQueue<u32> = StealQueue::new();
+//! let stealer = queue.stealer();
+//! queue.push(1);
+//! assert_eq!(stealer.steal(), Some(1));
+//! ```
+#[cfg(feature = "async")]
+pub use crate::async_value_pool;
+#[cfg(feature = "global")]
+pub use crate::global_pool;
+pub use crate::{
+    cache_padded, read_mostly_pool, steal_queue, sync_value_ref, thread_local_pool, waitable_pool,
+};
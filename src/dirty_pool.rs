@@ -0,0 +1,192 @@
+//! This module implements [`DirtyPool<T>`], a [`ValuePool<T>`] that remembers which positions changed.
+use crate::{ref_set::RefSet, ValuePool, ValueRef};
+
+/// A [`DirtyPool<T>`] wraps a [`ValuePool<T>`] and marks a position *dirty* whenever it might have changed:
+/// on [`push`](DirtyPool::push), [`get_mut`](DirtyPool::get_mut), [`replace`](DirtyPool::replace),
+/// [`update`](DirtyPool::update), [`take`](DirtyPool::take) and [`remove`](DirtyPool::remove).
+/// [`DirtyPool::drain_dirty`] returns and resets those positions, so only changed values need to be processed.
+/// ```
+/// use value_pool::dirty_pool::DirtyPool;
+///
+/// let mut sprites: DirtyPool<(i32, i32)> = DirtyPool::new();
+/// let player = sprites.push((0, 0));
+/// let enemy = sprites.push((5, 5));
+/// sprites.drain_dirty().for_each(drop); // first frame draws everything
+///
+/// sprites.update(player, |position| position.0 += 1);
+/// assert_eq!(sprites.get(enemy), Some(&(5, 5))); // reading doesn't mark anything
+/// assert_eq!(sprites.drain_dirty().collect::<Vec<_>>(), vec![player]);
+/// assert!(!sprites.is_dirty(player));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirtyPool<T> {
+    pool: ValuePool<T>,
+    dirty: RefSet<T>,
+}
+
+impl<T> Default for DirtyPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<ValuePool<T>> for DirtyPool<T> {
+    /// Wraps `pool`. No position is dirty at first.
+    #[inline]
+    fn from(pool: ValuePool<T>) -> Self {
+        DirtyPool {
+            pool,
+            dirty: (RefSet::new()),
+        }
+    }
+}
+
+impl<T> DirtyPool<T> {
+    /// Creates a new, empty [`DirtyPool`].
+    #[inline]
+    pub const fn new() -> DirtyPool<T> {
+        DirtyPool {
+            pool: (ValuePool::new()),
+            dirty: (RefSet::new()),
+        }
+    }
+
+    /// Gives read-only access to the underlying [`ValuePool`].
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<T> {
+        &self.pool
+    }
+
+    /// Returns the underlying [`ValuePool`], dropping the dirty marks.
+    #[inline]
+    pub fn into_pool(self) -> ValuePool<T> {
+        self.pool
+    }
+
+    /// Returns the number of elements stored.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.pool.element_count()
+    }
+
+    /// Returns true if no elements are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Returns true if the position `reference` changed since the last [`DirtyPool::drain_dirty`].
+    #[inline]
+    pub fn is_dirty(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        self.dirty.contains(reference)
+    }
+
+    /// Returns the number of dirty positions.
+    #[inline]
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Marks `reference` dirty, e.g. after a change trough interior mutability.
+    #[inline]
+    pub fn mark_dirty(&mut self, reference: impl Into<ValueRef<T>>) {
+        self.dirty.insert(reference);
+    }
+
+    /// Returns all dirty positions in ascending order and marks them clean.
+    /// Positions that got taken or removed are included, [`DirtyPool::get`] returns [`None`] for them.
+    #[inline]
+    pub fn drain_dirty(&mut self) -> impl Iterator<Item = ValueRef<T>> {
+        self.dirty.drain()
+    }
+
+    /// Same as [`ValuePool::push`]. Marks the new position dirty.
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let reference = self.pool.push(value);
+        self.dirty.insert(reference);
+        reference
+    }
+
+    /// Same as [`ValuePool::get`].
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.pool.get(reference)
+    }
+
+    /// Same as [`ValuePool::get_mut`]. Marks `reference` dirty if a value is stored there.
+    #[inline]
+    pub fn get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Option<&mut T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.get_mut(reference)?;
+        self.dirty.insert(reference);
+        Some(value)
+    }
+
+    /// Calls `f` with a mut borrow of the value at `reference` and marks it dirty.
+    /// Returns [`None`] if no value is stored there.
+    #[inline]
+    pub fn update<R>(
+        &mut self,
+        reference: impl Into<ValueRef<T>>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        self.get_mut(reference).map(f)
+    }
+
+    /// Same as [`ValuePool::replace`]. Marks `reference` dirty if it is in bounds.
+    #[inline]
+    pub fn replace(
+        &mut self,
+        reference: impl Into<ValueRef<T>>,
+        value: Option<T>,
+    ) -> Result<Option<T>, Option<T>> {
+        let reference: ValueRef<T> = reference.into();
+        let old = self.pool.replace(reference, value)?;
+        self.dirty.insert(reference);
+        Ok(old)
+    }
+
+    /// Same as [`ValuePool::take`]. Marks `reference` dirty if a value got taken.
+    #[inline]
+    pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.take(reference)?;
+        self.dirty.insert(reference);
+        Some(value)
+    }
+
+    /// Same as [`ValuePool::remove`]. Marks `reference` dirty if a value got removed.
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.remove(reference)?;
+        self.dirty.insert(reference);
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DirtyPool;
+    use crate::ValuePool;
+
+    #[test]
+    fn test_marks() {
+        let mut source = ValuePool::new();
+        let first = source.push(1);
+        let mut pool: DirtyPool<u32> = source.into();
+        assert_eq!(pool.dirty_count(), 0);
+
+        let second = pool.push(2);
+        assert_eq!(pool.remove(first), Some(1));
+        assert_eq!(pool.remove(first), None);
+        assert_eq!(pool.get_mut(first), None);
+        assert_eq!(pool.drain_dirty().collect::<Vec<_>>(), vec![first, second]);
+
+        assert_eq!(pool.replace(second, Some(3)), Ok(Some(2)));
+        assert!(pool.is_dirty(second));
+        assert_eq!(pool.update(second, |v| *v * 2), Some(6));
+        assert_eq!(pool.dirty_count(), 1);
+    }
+}
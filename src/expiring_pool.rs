@@ -0,0 +1,223 @@
+//! This module implements [`ExpiringPool<T, D>`], a [`ValuePool<T>`] whose values can carry a deadline after which they get swept out.
+use std::{collections::BTreeSet, time::Instant};
+
+use crate::{ValuePool, ValueRef};
+
+/// An [`ExpiringPool<T, D>`] stores values with an optional deadline of type `D`, which is an [`Instant`] by default
+/// but can be anything ordered, e.g. a generation or frame counter.
+/// [`ExpiringPool::expire`] removes all values whose deadline passed, using an ordered index of the deadlines,
+/// so only the expired values are visited.
+/// ```
+/// use value_pool::expiring_pool::ExpiringPool;
+///
+/// // deadlines are generation counts
+/// let mut sessions: ExpiringPool<&str, u64> = ExpiringPool::new();
+/// let alice = sessions.push_with_deadline("alice", 3);
+/// let bob = sessions.push_with_deadline("bob", 5);
+/// let admin = sessions.push("admin");
+///
+/// assert_eq!(sessions.expire(2), vec![]);
+/// assert_eq!(sessions.expire(4), vec![(alice, "alice")]);
+/// sessions.set_deadline(bob, Some(10));
+/// assert_eq!(sessions.expire(6), vec![]);
+/// assert_eq!(sessions.get(admin), Some(&"admin"));
+/// assert_eq!(sessions.next_deadline(), Some(10));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExpiringPool<T, D: Ord + Copy = Instant> {
+    pool: ValuePool<T>,
+    // deadline of every position, by index
+    deadlines: Vec<Option<D>>,
+    index: BTreeSet<(D, ValueRef<T>)>,
+}
+
+impl<T, D: Ord + Copy> Default for ExpiringPool<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, D: Ord + Copy> ExpiringPool<T, D> {
+    /// Creates a new, empty [`ExpiringPool`].
+    #[inline]
+    pub const fn new() -> ExpiringPool<T, D> {
+        ExpiringPool {
+            pool: (ValuePool::new()),
+            deadlines: (Vec::new()),
+            index: (BTreeSet::new()),
+        }
+    }
+
+    /// Gives read-only access to the underlying [`ValuePool`].
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<T> {
+        &self.pool
+    }
+
+    /// Returns the number of elements stored, expired or not.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.pool.element_count()
+    }
+
+    /// Returns true if no elements are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Pushes `value` without a deadline.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let reference = self.pool.push(value);
+        self.store_deadline(reference, None);
+        reference
+    }
+
+    /// Pushes `value`, which expires once [`ExpiringPool::expire`] gets called with a time `>= deadline`.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn push_with_deadline(&mut self, value: T, deadline: D) -> ValueRef<T> {
+        let reference = self.pool.push(value);
+        self.store_deadline(reference, Some(deadline));
+        reference
+    }
+
+    /// Sets the deadline of `reference` and updates the index.
+    #[inline]
+    fn store_deadline(&mut self, reference: ValueRef<T>, deadline: Option<D>) {
+        let index = reference.index.get();
+        if index >= self.deadlines.len() {
+            self.deadlines.resize(index + 1, None);
+        }
+        if let Some(old) = std::mem::replace(&mut self.deadlines[index], deadline) {
+            self.index.remove(&(old, reference));
+        }
+        if let Some(deadline) = deadline {
+            self.index.insert((deadline, reference));
+        }
+    }
+
+    /// Returns the deadline of the value at `reference`.
+    #[inline]
+    pub fn deadline(&self, reference: impl Into<ValueRef<T>>) -> Option<D> {
+        let reference: ValueRef<T> = reference.into();
+        if !self.pool.has_item(reference) {
+            return None;
+        }
+        self.deadlines.get(reference.index.get()).copied().flatten()
+    }
+
+    /// Replaces the deadline of the value at `reference`; [`None`] makes it never expire.
+    /// Returns false if no value is stored there.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    pub fn set_deadline(&mut self, reference: impl Into<ValueRef<T>>, deadline: Option<D>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        if !self.pool.has_item(reference) {
+            return false;
+        }
+        self.store_deadline(reference, deadline);
+        true
+    }
+
+    /// Returns the earliest deadline of all stored values.
+    #[inline]
+    pub fn next_deadline(&self) -> Option<D> {
+        self.index.first().map(|(deadline, _)| *deadline)
+    }
+
+    /// Same as [`ValuePool::get`]. Values whose deadline passed are returned until [`ExpiringPool::expire`] removes them.
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.pool.get(reference)
+    }
+
+    /// Same as [`ValuePool::get_mut`].
+    #[inline]
+    pub fn get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Option<&mut T> {
+        self.pool.get_mut(reference)
+    }
+
+    /// Same as [`ValuePool::take`].
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.take(reference)?;
+        self.store_deadline(reference, None);
+        Some(value)
+    }
+
+    /// Removes the value at `reference` and returns it, same as [`ExpiringPool::take`].
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        self.take(reference)
+    }
+
+    /// Removes and returns all values whose deadline is `<= now`, ordered by deadline.
+    ///
+    /// # Complexity
+    /// `O(k log n)` where `k` is the number of expired values
+    pub fn expire(&mut self, now: D) -> Vec<(ValueRef<T>, T)> {
+        let mut expired = Vec::new();
+        while let Some(&(deadline, reference)) = self.index.first() {
+            if deadline > now {
+                break;
+            }
+            self.index.pop_first();
+            self.deadlines[reference.index.get()] = None;
+            if let Some(value) = self.pool.take(reference) {
+                expired.push((reference, value));
+            }
+        }
+        expired
+    }
+
+    /// Clears this [`ExpiringPool`].
+    #[inline]
+    pub fn clear(&mut self) {
+        self.pool.clear();
+        self.deadlines.clear();
+        self.index.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExpiringPool;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_expire_instants() {
+        let start = Instant::now();
+        let mut cache: ExpiringPool<u32> = ExpiringPool::new();
+        let short = cache.push_with_deadline(1, start + Duration::from_secs(1));
+        let long = cache.push_with_deadline(2, start + Duration::from_secs(60));
+        let taken = cache.push_with_deadline(3, start);
+
+        assert_eq!(cache.take(taken), Some(3));
+        assert!(!cache.set_deadline(taken, None));
+        assert_eq!(
+            cache.expire(start + Duration::from_secs(2)),
+            vec![(short, 1)]
+        );
+        assert_eq!(cache.deadline(short), None);
+
+        let deadline = cache.deadline(long);
+        assert!(cache.set_deadline(long, deadline));
+        assert_eq!(cache.next_deadline(), deadline);
+        assert!(cache.set_deadline(long, None));
+        assert_eq!(cache.next_deadline(), None);
+        assert!(cache.expire(start + Duration::from_secs(120)).is_empty());
+        assert_eq!(cache.element_count(), 1);
+    }
+}
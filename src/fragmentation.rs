@@ -0,0 +1,136 @@
+//! This module implements [`ValuePool::fragmentation_report`], which describes the empty positions of a pool and how to compact it.
+use crate::{ValuePool, ValueRef};
+use std::ops::Range;
+
+/// Describes how fragmented a [`ValuePool<T>`] is, returned by [`ValuePool::fragmentation_report`].
+/// The report is only valid until the pool gets modified. Fields may be added in the future.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct FragmentationReport<T> {
+    /// Number of slots, occupied or empty.
+    pub slots: usize,
+    /// Number of empty slots.
+    pub vacant: usize,
+    /// The positions of the longest run of occupied slots, the first one if several are equally long.
+    /// Empty if no slot is occupied.
+    pub largest_occupied_run: Range<usize>,
+    /// Number of slots per region of [`FragmentationReport::vacancies_per_region`].
+    pub region_size: usize,
+    /// Number of empty slots per region: entry `i` counts the positions `i * region_size..(i + 1) * region_size`.
+    pub vacancies_per_region: Vec<usize>,
+    /// Moves `(from, to)` that leave no empty position below an occupied one, except positions kept empty by pinned items.
+    /// Applying them in order with [`ValuePool::relocate`] performs exactly these moves, so the cost of the compaction is
+    /// the length of this plan; it can be spread over several idle frames.
+    pub compaction_plan: Vec<(ValueRef<T>, ValueRef<T>)>,
+}
+
+impl<T> Clone for FragmentationReport<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        FragmentationReport {
+            slots: (self.slots),
+            vacant: (self.vacant),
+            largest_occupied_run: (self.largest_occupied_run.clone()),
+            region_size: (self.region_size),
+            vacancies_per_region: (self.vacancies_per_region.clone()),
+            compaction_plan: (self.compaction_plan.clone()),
+        }
+    }
+}
+
+impl<T> ValuePool<T> {
+    /// Returns a [`FragmentationReport`] of this [`ValuePool`], counting empty slots per `region_size` positions.
+    /// A `region_size` of 0 is treated as 1.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let refs: Vec<_> = (0..6).map(|value| pool.push(value)).collect();
+    /// pool.take(refs[0]);
+    /// pool.take(refs[3]);
+    ///
+    /// let report = pool.fragmentation_report(3);
+    /// assert_eq!(report.largest_occupied_run, 1..3);
+    /// assert_eq!(report.vacancies_per_region, vec![1, 1]);
+    /// assert_eq!(report.compaction_plan, vec![(refs[5], refs[0]), (refs[4], refs[3])]);
+    ///
+    /// for (from, to) in report.compaction_plan {
+    ///     assert_eq!(pool.relocate(from), to);
+    /// }
+    /// assert_eq!(pool.fragmentation_report(3).vacant, 0);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn fragmentation_report(&self, region_size: usize) -> FragmentationReport<T> {
+        let region_size = region_size.max(1);
+        let mut vacancies_per_region = vec![0; self.store.len().div_ceil(region_size)];
+        let mut largest_occupied_run = 0..0;
+        let mut run_start = 0;
+        for (index, slot) in self.store.iter().enumerate() {
+            if slot.is_none() {
+                vacancies_per_region[index / region_size] += 1;
+                run_start = index + 1;
+            } else if index + 1 - run_start > largest_occupied_run.len() {
+                largest_occupied_run = run_start..index + 1;
+            }
+        }
+
+        let mut vacancies = (0..self.store.len()).filter(|index| self.store[*index].is_none());
+        let mut movable = (0..self.store.len())
+            .rev()
+            .map(ValueRef::new)
+            .filter(|reference| self.has_item(*reference) && !self.is_pinned(*reference));
+        let mut compaction_plan = Vec::new();
+        while let (Some(to), Some(from)) = (vacancies.next(), movable.next()) {
+            if to > from.index.get() {
+                break;
+            }
+            compaction_plan.push((from, ValueRef::new(to)));
+        }
+
+        FragmentationReport {
+            slots: (self.store.len()),
+            vacant: (vacancies_per_region.iter().sum()),
+            largest_occupied_run,
+            region_size,
+            vacancies_per_region,
+            compaction_plan,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_plan_skips_pinned() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        for value in 0..8 {
+            pool.push(value);
+        }
+        for index in [0, 1, 5] {
+            pool.take(ValueRef::new(index));
+        }
+        pool.pin_slot(ValueRef::new(7));
+        let report = pool.fragmentation_report(0);
+        assert_eq!((report.slots, report.vacant), (8, 3));
+        assert_eq!(report.largest_occupied_run, 2..5);
+        assert_eq!(report.vacancies_per_region, vec![1, 1, 0, 0, 0, 1, 0, 0]);
+        let plan: Vec<(usize, usize)> = report
+            .compaction_plan
+            .iter()
+            .map(|(from, to)| (from.index.get(), to.index.get()))
+            .collect();
+        assert_eq!(plan, vec![(6, 0), (4, 1)]);
+        for (from, to) in report.compaction_plan {
+            assert_eq!(pool.relocate(from), to);
+        }
+        assert!(pool.fragmentation_report(1).compaction_plan.is_empty());
+        assert!(ValuePool::<u32>::new()
+            .fragmentation_report(4)
+            .largest_occupied_run
+            .is_empty());
+    }
+}
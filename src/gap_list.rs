@@ -0,0 +1,339 @@
+//! This module implements [`GapList<T>`], a list optimized for many insertions and deletions around a moving cursor (like the text buffer of an editor).
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug, Clone)]
+struct GapNode<T> {
+    value: T,
+    prev: Option<ValueRef<GapNode<T>>>,
+    next: Option<ValueRef<GapNode<T>>>,
+}
+
+/// A [`GapList<T>`] stores its elements as a doubly linked list inside a [`ValuePool`] and keeps a cursor.
+/// The cursor sits *between* two elements, like the caret of a text editor.
+/// Inserting and deleting at the cursor is `O(1)`, moving the cursor costs `O(distance)`.
+/// ```
+/// use value_pool::gap_list::GapList;
+///
+/// let mut text: GapList<char> = GapList::new();
+/// for c in "helo".chars() {
+///     text.insert_at_cursor(c);
+/// }
+/// assert_eq!(text.cursor_position(), 4);
+///
+/// text.move_cursor(-1);
+/// text.insert_at_cursor('l');
+/// assert_eq!(text.iter().collect::<String>(), "hello");
+///
+/// text.move_cursor_to(0);
+/// assert_eq!(text.delete_at_cursor(), Some('h'));
+/// text.insert_at_cursor('j');
+/// assert_eq!(text.iter().collect::<String>(), "jello");
+/// ```
+#[derive(Debug, Clone)]
+pub struct GapList<T> {
+    store: ValuePool<GapNode<T>>,
+    start: Option<ValueRef<GapNode<T>>>,
+    end: Option<ValueRef<GapNode<T>>>,
+    // element right after the cursor; `None` if the cursor is at the end
+    cursor: Option<ValueRef<GapNode<T>>>,
+    position: usize,
+}
+
+impl<T> Default for GapList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GapList<T> {
+    /// Creates a new, empty [`GapList`]. This doesn't allocate and can be used in `static`s.
+    #[inline]
+    pub const fn new() -> GapList<T> {
+        GapList {
+            store: (ValuePool::new()),
+            start: None,
+            end: None,
+            cursor: None,
+            position: 0,
+        }
+    }
+
+    /// Creates a new [`GapList`] that can store `capacity` many items.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> GapList<T> {
+        GapList {
+            store: (ValuePool::with_capacity(capacity)),
+            start: None,
+            end: None,
+            cursor: None,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.store.element_count()
+    }
+
+    /// Returns true if no elements are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Returns the number of elements before the cursor.
+    #[inline]
+    pub fn cursor_position(&self) -> usize {
+        self.position
+    }
+
+    #[inline]
+    fn before_cursor(&self) -> Option<ValueRef<GapNode<T>>> {
+        match self.cursor {
+            Some(cursor) => self.store.get(cursor)?.prev,
+            None => self.end,
+        }
+    }
+
+    /// Moves the cursor by `offset` elements (backwards if negative). The cursor stops at the start and the end.
+    /// Returns the new [cursor position](GapList::cursor_position).
+    ///
+    /// # Complexity
+    /// `O(|offset|)`
+    #[inline]
+    pub fn move_cursor(&mut self, offset: isize) -> usize {
+        if offset >= 0 {
+            for _ in 0..offset {
+                let Some(node) = self.cursor.and_then(|cursor| self.store.get(cursor)) else {
+                    break;
+                };
+                self.cursor = node.next;
+                self.position += 1;
+            }
+        } else {
+            for _ in 0..offset.unsigned_abs() {
+                let Some(prev) = self.before_cursor() else {
+                    break;
+                };
+                self.cursor = Some(prev);
+                self.position -= 1;
+            }
+        }
+        self.position
+    }
+
+    /// Moves the cursor to `position`, so that `position` many elements are before it. `position` gets clamped to [`GapList::len`].
+    ///
+    /// # Complexity
+    /// `O(min(position, |position - cursor_position|, len - position))`
+    #[inline]
+    pub fn move_cursor_to(&mut self, position: usize) -> usize {
+        let position = position.min(self.len());
+        let from_cursor = position.abs_diff(self.position);
+        let from_end = self.len() - position;
+        if position < from_cursor && position <= from_end {
+            // walking from the start is shortest
+            self.cursor = self.start;
+            self.position = 0;
+        } else if from_end < from_cursor {
+            // walking from the end is shorter
+            self.cursor = None;
+            self.position = self.len();
+        }
+        self.move_cursor(position as isize - self.position as isize)
+    }
+
+    /// Returns the element right after the cursor.
+    #[inline]
+    pub fn peek_at_cursor(&self) -> Option<&T> {
+        self.store.get(self.cursor?).map(|node| &node.value)
+    }
+
+    /// Returns the element right after the cursor.
+    #[inline]
+    pub fn peek_mut_at_cursor(&mut self) -> Option<&mut T> {
+        self.store.get_mut(self.cursor?).map(|node| &mut node.value)
+    }
+
+    /// Inserts `value` before the cursor. The cursor ends up after the inserted element.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn insert_at_cursor(&mut self, value: T) {
+        let prev = self.before_cursor();
+        let reference = self.store.push(GapNode {
+            value,
+            prev,
+            next: self.cursor,
+        });
+        match prev.and_then(|prev| self.store.get_mut(prev)) {
+            Some(prev) => prev.next = Some(reference),
+            None => self.start = Some(reference),
+        }
+        match self.cursor.and_then(|cursor| self.store.get_mut(cursor)) {
+            Some(cursor) => cursor.prev = Some(reference),
+            None => self.end = Some(reference),
+        }
+        self.position += 1;
+    }
+
+    /// Inserts clones of all `values` before the cursor, in order. The cursor ends up after the inserted elements.
+    /// Storage for all of them is reserved up front.
+    /// ```
+    /// use value_pool::gap_list::GapList;
+    ///
+    /// let mut samples: GapList<u16> = GapList::new();
+    /// samples.extend_from_slice(&[1, 4]);
+    /// samples.move_cursor(-1);
+    /// samples.extend_from_slice(&[2, 3]);
+    /// assert_eq!(samples.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// assert_eq!(samples.cursor_position(), 3);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of `values`
+    #[inline]
+    pub fn extend_from_slice(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        self.store.reserve(values.len());
+        for value in values {
+            self.insert_at_cursor(value.clone());
+        }
+    }
+
+    #[inline]
+    fn unlink(&mut self, reference: ValueRef<GapNode<T>>) -> Option<GapNode<T>> {
+        let node = self.store.take(reference)?;
+        match node.prev.and_then(|prev| self.store.get_mut(prev)) {
+            Some(prev) => prev.next = node.next,
+            None => self.start = node.next,
+        }
+        match node.next.and_then(|next| self.store.get_mut(next)) {
+            Some(next) => next.prev = node.prev,
+            None => self.end = node.prev,
+        }
+        Some(node)
+    }
+
+    /// Removes the element right after the cursor (like the `Delete` key).
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn delete_at_cursor(&mut self) -> Option<T> {
+        let node = self.unlink(self.cursor?)?;
+        self.cursor = node.next;
+        Some(node.value)
+    }
+
+    /// Removes the element right before the cursor (like the `Backspace` key).
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn delete_before_cursor(&mut self) -> Option<T> {
+        let node = self.unlink(self.before_cursor()?)?;
+        self.position -= 1;
+        Some(node.value)
+    }
+
+    /// Iterates over all elements from start to end.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut current = self.start;
+        std::iter::from_fn(move || {
+            let node = self.store.get(current?)?;
+            current = node.next;
+            Some(&node.value)
+        })
+    }
+
+    /// Removes all elements and resets the cursor.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.store.clear();
+        self.start = None;
+        self.end = None;
+        self.cursor = None;
+        self.position = 0;
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for GapList<T> {
+    /// Inserts all values before the cursor, see [`GapList::extend_from_slice`].
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.store.reserve(iter.size_hint().0);
+        for value in iter {
+            self.insert_at_cursor(*value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GapList;
+
+    #[test]
+    fn test_cursor_stays_in_bounds() {
+        let mut list: GapList<u32> = GapList::new();
+        assert_eq!(list.move_cursor(-3), 0);
+        assert_eq!(list.delete_before_cursor(), None);
+        assert_eq!(list.delete_at_cursor(), None);
+
+        for i in 0..5 {
+            list.insert_at_cursor(i);
+        }
+        assert_eq!(list.move_cursor(3), 5);
+        assert_eq!(list.move_cursor(-10), 0);
+        assert_eq!(list.move_cursor_to(4), 4);
+        assert_eq!(list.peek_at_cursor(), Some(&4));
+
+        assert_eq!(list.delete_before_cursor(), Some(3));
+        assert_eq!(list.delete_at_cursor(), Some(4));
+        assert_eq!(list.cursor_position(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        list.move_cursor_to(0);
+        while list.delete_at_cursor().is_some() {}
+        assert!(list.is_empty());
+        list.insert_at_cursor(7);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![7]);
+    }
+
+    #[test]
+    fn test_extend_at_cursor() {
+        let mut list: GapList<u32> = GapList::new();
+        list.extend(&[0, 3]);
+        list.move_cursor_to(1);
+        list.extend([1, 2].iter());
+        assert_eq!(list.cursor_position(), 3);
+        assert_eq!(list.peek_at_cursor(), Some(&3));
+        list.extend_from_slice(&[]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_move_cursor_to_shortest_walk() {
+        let mut list: GapList<u32> = GapList::new();
+        list.extend_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        // walks from the start, the cursor and the end
+        for position in [1, 6, 9, 4, 0, 10, 3] {
+            assert_eq!(list.move_cursor_to(position), position);
+            assert_eq!(
+                list.peek_at_cursor(),
+                [0, 1, 2, 3, 4, 5, 6, 7, 8, 9].get(position)
+            );
+        }
+        list.insert_at_cursor(10);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 10, 3, 4, 5, 6, 7, 8, 9]
+        );
+    }
+}
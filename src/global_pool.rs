@@ -0,0 +1,82 @@
+//! This module implements [`GlobalPool<T>`], one global [`ValuePool<T>`] per type `T`, as well as the free functions [`gpush`], [`gget`] and [`gtake`].
+//! It's meant for prototypes and tests that don't want to pass a pool trough every function.
+//!
+//! Only available with the `global` feature.
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{Mutex, OnceLock, RwLock},
+};
+
+use crate::{ValuePool, ValueRef};
+
+type Registry = Mutex<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Gives access to the global [`ValuePool<T>`] of `T`. The pool is created on first use and lives until the program ends.
+/// ```
+/// use value_pool::global_pool::{gget, gpush, gtake, GlobalPool};
+///
+/// let reference = gpush(12u32);
+/// assert_eq!(gget(reference), Some(12u32));
+///
+/// GlobalPool::<u32>::write(|pool| *pool.get_mut(reference).unwrap() += 1);
+/// assert_eq!(gtake(reference), Some(13u32));
+///
+/// // every type has its own pool
+/// assert!(GlobalPool::<u64>::read(|pool| pool.is_empty()));
+/// ```
+///
+/// Note: A poisoned lock is ignored, the pool is handed out anyway.
+#[derive(Debug)]
+pub struct GlobalPool<T> {
+    type_info: PhantomData<T>,
+}
+
+impl<T: Send + Sync + 'static> GlobalPool<T> {
+    /// Returns the lock protecting the global [`ValuePool<T>`].
+    #[inline]
+    pub fn lock() -> &'static RwLock<ValuePool<T>> {
+        let mut registry = REGISTRY
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let pool: &'static (dyn Any + Send + Sync) = *registry
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::leak(Box::new(RwLock::new(ValuePool::<T>::new()))));
+        pool.downcast_ref::<RwLock<ValuePool<T>>>()
+            .expect("Registry to store a pool of the right type")
+    }
+
+    /// Runs `f` with shared access to the global [`ValuePool<T>`].
+    #[inline]
+    pub fn read<R>(f: impl FnOnce(&ValuePool<T>) -> R) -> R {
+        f(&Self::lock().read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Runs `f` with exclusive access to the global [`ValuePool<T>`].
+    #[inline]
+    pub fn write<R>(f: impl FnOnce(&mut ValuePool<T>) -> R) -> R {
+        f(&mut Self::lock().write().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Same as [`ValuePool<T>::push`] on the global pool of `T`.
+#[inline]
+pub fn gpush<T: Send + Sync + 'static>(value: T) -> ValueRef<T> {
+    GlobalPool::<T>::write(|pool| pool.push(value))
+}
+
+/// Returns a clone of the value at `reference` in the global pool of `T`.
+#[inline]
+pub fn gget<T: Clone + Send + Sync + 'static>(reference: impl Into<ValueRef<T>>) -> Option<T> {
+    GlobalPool::<T>::read(|pool| pool.get(reference).cloned())
+}
+
+/// Same as [`ValuePool<T>::take`] on the global pool of `T`.
+#[inline]
+pub fn gtake<T: Send + Sync + 'static>(reference: impl Into<ValueRef<T>>) -> Option<T> {
+    GlobalPool::<T>::write(|pool| pool.take(reference))
+}
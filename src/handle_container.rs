@@ -0,0 +1,137 @@
+//! This module implements [`HandleContainer<T>`], a trait for containers that hand out handles to their values.
+use crate::{dirty_pool::DirtyPool, expiring_pool::ExpiringPool, ValuePool, ValueRef};
+
+/// A [`HandleContainer<T>`] stores values of type `T` and addresses them with a [`HandleContainer::Handle`].
+/// Libraries can use it to be generic over the pool flavor their users pick.
+/// ```
+/// use value_pool::{ValuePool, dirty_pool::DirtyPool, handle_container::HandleContainer};
+///
+/// fn double_all<C: HandleContainer<u32>>(container: &mut C, handles: &[C::Handle]) {
+///     for handle in handles {
+///         if let Some(value) = container.get_mut(*handle) {
+///             *value *= 2;
+///         }
+///     }
+/// }
+///
+/// let mut pool: ValuePool<u32> = ValuePool::new();
+/// let handles = [HandleContainer::insert(&mut pool, 1), HandleContainer::insert(&mut pool, 2)];
+/// double_all(&mut pool, &handles);
+/// assert_eq!(pool.get(handles[1]), Some(&4));
+///
+/// let mut dirty: DirtyPool<u32> = DirtyPool::new();
+/// let handle = dirty.push(3);
+/// double_all(&mut dirty, &[handle]);
+/// assert_eq!(dirty.get(handle), Some(&6));
+/// ```
+pub trait HandleContainer<T> {
+    /// The handle type used to address values.
+    type Handle: Copy;
+
+    /// Gets a borrow of the value at `handle`.
+    fn get(&self, handle: Self::Handle) -> Option<&T>;
+
+    /// Gets a mut borrow of the value at `handle`.
+    fn get_mut(&mut self, handle: Self::Handle) -> Option<&mut T>;
+
+    /// Stores `value` and returns its handle.
+    fn insert(&mut self, value: T) -> Self::Handle;
+
+    /// Removes the value at `handle` and returns it.
+    fn remove(&mut self, handle: Self::Handle) -> Option<T>;
+}
+
+impl<T> HandleContainer<T> for ValuePool<T> {
+    type Handle = ValueRef<T>;
+
+    #[inline]
+    fn get(&self, handle: ValueRef<T>) -> Option<&T> {
+        ValuePool::get(self, handle)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, handle: ValueRef<T>) -> Option<&mut T> {
+        ValuePool::get_mut(self, handle)
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> ValueRef<T> {
+        self.push(value)
+    }
+
+    #[inline]
+    fn remove(&mut self, handle: ValueRef<T>) -> Option<T> {
+        ValuePool::remove(self, handle)
+    }
+}
+
+impl<T> HandleContainer<T> for DirtyPool<T> {
+    type Handle = ValueRef<T>;
+
+    #[inline]
+    fn get(&self, handle: ValueRef<T>) -> Option<&T> {
+        DirtyPool::get(self, handle)
+    }
+
+    /// Same as [`DirtyPool::get_mut`], so the value gets marked dirty.
+    #[inline]
+    fn get_mut(&mut self, handle: ValueRef<T>) -> Option<&mut T> {
+        DirtyPool::get_mut(self, handle)
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> ValueRef<T> {
+        self.push(value)
+    }
+
+    #[inline]
+    fn remove(&mut self, handle: ValueRef<T>) -> Option<T> {
+        self.take(handle)
+    }
+}
+
+impl<T, D: Ord + Copy> HandleContainer<T> for ExpiringPool<T, D> {
+    type Handle = ValueRef<T>;
+
+    #[inline]
+    fn get(&self, handle: ValueRef<T>) -> Option<&T> {
+        ExpiringPool::get(self, handle)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, handle: ValueRef<T>) -> Option<&mut T> {
+        ExpiringPool::get_mut(self, handle)
+    }
+
+    /// Same as [`ExpiringPool::push`], the value never expires.
+    #[inline]
+    fn insert(&mut self, value: T) -> ValueRef<T> {
+        self.push(value)
+    }
+
+    #[inline]
+    fn remove(&mut self, handle: ValueRef<T>) -> Option<T> {
+        self.take(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HandleContainer;
+    use crate::{expiring_pool::ExpiringPool, ValuePool};
+
+    fn round_trip<C: HandleContainer<&'static str>>(container: &mut C) {
+        let handle = container.insert("a");
+        assert_eq!(container.get(handle), Some(&"a"));
+        *container.get_mut(handle).unwrap() = "b";
+        assert_eq!(container.remove(handle), Some("b"));
+        assert_eq!(container.get(handle), None);
+        assert_eq!(container.remove(handle), None);
+    }
+
+    #[test]
+    fn test_generic_round_trip() {
+        round_trip(&mut ValuePool::new());
+        round_trip(&mut ExpiringPool::<_, u64>::new());
+    }
+}
@@ -0,0 +1,98 @@
+//! This module implements [`ValuePool::binary_layout`], which describes how a pool is laid out in memory for external tools.
+use crate::ValuePool;
+
+/// Describes the memory layout of one [`ValuePool<T>`], returned by [`ValuePool::binary_layout`].
+/// Meant for external tools like debuggers, visualizers or memory profilers that read the memory of a pool.
+///
+/// The slots are stored consecutively starting at [`BinaryLayout::data_address`], each one is an [`Option<T>`].
+/// How [`Option<T>`] marks a slot as empty is decided by the compiler and differs between types,
+/// that's why the occupancy is given separately as [`BinaryLayout::occupancy`].
+/// Fields may be added in the future.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BinaryLayout {
+    /// Address of the first slot.
+    pub data_address: usize,
+    /// Distance in bytes from one slot to the next one.
+    pub slot_stride: usize,
+    /// Alignment of the slots in bytes.
+    pub slot_align: usize,
+    /// Offset in bytes of the `T` inside an occupied slot. [`None`] if no slot is occupied, so it couldn't be measured.
+    pub value_offset: Option<usize>,
+    /// Size of `T` in bytes.
+    pub value_size: usize,
+    /// Number of slots, occupied or empty.
+    pub slots: usize,
+    /// Number of slots that fit into the allocation.
+    pub capacity: usize,
+    /// Bitmap of the occupied slots: slot `i` is occupied if bit `i % 64` of word `i / 64` is set.
+    pub occupancy: Vec<u64>,
+}
+
+impl<T> ValuePool<T> {
+    /// Returns a description of the current memory layout of this [`ValuePool`].
+    /// The description is only valid until the pool gets modified.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let first = pool.push(1);
+    /// pool.push(2);
+    /// pool.take(first);
+    ///
+    /// let layout = pool.binary_layout();
+    /// assert_eq!(layout.slots, 2);
+    /// assert_eq!(layout.occupancy, vec![0b10]);
+    /// assert_eq!(layout.slot_stride, std::mem::size_of::<Option<u32>>());
+    /// assert_eq!(layout.value_size, 4);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn binary_layout(&self) -> BinaryLayout {
+        let mut occupancy = vec![0u64; self.store.len().div_ceil(64)];
+        let mut value_offset = None;
+        for (index, slot) in self.store.iter().enumerate() {
+            if let Some(value) = slot {
+                occupancy[index / 64] |= 1 << (index % 64);
+                value_offset.get_or_insert_with(|| {
+                    value as *const T as usize - slot as *const Option<T> as usize
+                });
+            }
+        }
+        BinaryLayout {
+            data_address: (self.store.as_ptr() as usize),
+            slot_stride: (std::mem::size_of::<Option<T>>()),
+            slot_align: (std::mem::align_of::<Option<T>>()),
+            value_offset,
+            value_size: (std::mem::size_of::<T>()),
+            slots: (self.store.len()),
+            capacity: (self.store.capacity()),
+            occupancy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_layout_matches_memory() {
+        let mut pool: ValuePool<u64> = ValuePool::new();
+        for value in 0..70 {
+            pool.push(value);
+        }
+        pool.take(ValueRef::new(0));
+        let layout = pool.binary_layout();
+        assert_eq!(layout.occupancy, vec![u64::MAX - 1, 0b11_1111]);
+
+        let offset = layout.value_offset.unwrap();
+        let address = layout.data_address + 65 * layout.slot_stride + offset;
+        assert_eq!(
+            address,
+            pool.get(ValueRef::new(65)).unwrap() as *const u64 as usize
+        );
+        assert_eq!(ValuePool::<u64>::new().binary_layout().value_offset, None);
+    }
+}
@@ -0,0 +1,322 @@
+//! This module implements [`KeyedPool<K, V>`] which stores `(K, V)` pairs in a [`ValuePool`] and keeps an index from `K` to the position of the pair.
+//! The pool and index pair is [`IndexedPool<K, V, I>`], which [`OrderedPoolMap<K, V>`](crate::ordered_pool_map::OrderedPoolMap) shares.
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use crate::{ValuePool, ValueRef};
+
+/// An index from keys to the positions of their pairs in an [`IndexedPool<K, V, I>`].
+/// Implemented for [`HashMap`] ([`KeyedPool<K, V>`]) and [`BTreeMap`](std::collections::BTreeMap)
+/// ([`OrderedPoolMap<K, V>`](crate::ordered_pool_map::OrderedPoolMap)).
+pub trait PairIndex<K, V> {
+    /// Returns the position stored for `key`.
+    fn position(&self, key: &K) -> Option<ValueRef<(K, V)>>;
+
+    /// Stores `reference` as the position of `key`.
+    fn insert_position(&mut self, key: K, reference: ValueRef<(K, V)>);
+
+    /// Removes `key` and returns its position.
+    fn remove_position(&mut self, key: &K) -> Option<ValueRef<(K, V)>>;
+
+    /// Returns the number of indexed keys.
+    fn len(&self) -> usize;
+
+    /// Returns true if no keys are indexed.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all keys.
+    fn clear(&mut self);
+}
+
+impl<K: Hash + Eq, V> PairIndex<K, V> for HashMap<K, ValueRef<(K, V)>> {
+    #[inline]
+    fn position(&self, key: &K) -> Option<ValueRef<(K, V)>> {
+        self.get(key).copied()
+    }
+
+    #[inline]
+    fn insert_position(&mut self, key: K, reference: ValueRef<(K, V)>) {
+        self.insert(key, reference);
+    }
+
+    #[inline]
+    fn remove_position(&mut self, key: &K) -> Option<ValueRef<(K, V)>> {
+        self.remove(key)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+}
+
+/// [`IndexedPool<K, V, I>`] stores `(K, V)` pairs in a [`ValuePool<(K, V)>`] and maintains the [`PairIndex`] `I` next to it.
+/// Values can be accessed by key or by the [`ValueRef`] returned on insertion.
+/// Use it trough [`KeyedPool<K, V>`] or [`OrderedPoolMap<K, V>`](crate::ordered_pool_map::OrderedPoolMap).
+#[derive(Debug, Clone)]
+pub struct IndexedPool<K, V, I> {
+    pub(crate) pool: ValuePool<(K, V)>,
+    pub(crate) index: I,
+}
+
+/// [`KeyedPool<K, V>`] stores `(K, V)` pairs in a [`ValuePool<(K, V)>`] and maintains a [`HashMap<K, ValueRef<(K, V)>>`] next to it.
+/// Values can be accessed by key or by the [`ValueRef`] returned on insertion.
+/// ```
+/// use value_pool::keyed_pool::KeyedPool;
+///
+/// let mut pool: KeyedPool<String, u32> = KeyedPool::new();
+/// let (alice_ref, old) = pool.insert("alice".to_string(), 31);
+/// assert_eq!(old, None);
+/// pool.insert("bob".to_string(), 42);
+///
+/// assert_eq!(pool.get("alice"), Some(&31));
+/// assert_eq!(pool.get_by_ref(alice_ref), Some((&"alice".to_string(), &31)));
+///
+/// // Inserting an existing key keeps its position
+/// let (new_alice_ref, old) = pool.insert("alice".to_string(), 32);
+/// assert_eq!(old, Some(31));
+/// assert_eq!(new_alice_ref, alice_ref);
+///
+/// assert_eq!(pool.remove("bob"), Some(42));
+/// assert_eq!(pool.element_count(), 1);
+/// ```
+pub type KeyedPool<K, V> = IndexedPool<K, V, HashMap<K, ValueRef<(K, V)>>>;
+
+impl<K, V, I: Default> Default for IndexedPool<K, V, I> {
+    fn default() -> Self {
+        IndexedPool {
+            pool: (ValuePool::new()),
+            index: (I::default()),
+        }
+    }
+}
+
+impl<K: Clone, V, I: PairIndex<K, V>> IndexedPool<K, V, I> {
+    /// Returns the number of stored pairs.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns true if no pairs are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Gives read-only access to the underlying [`ValuePool`].
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<(K, V)> {
+        &self.pool
+    }
+
+    /// Inserts `value` under `key` and returns the position of the pair.
+    /// If `key` was already stored, its value gets replaced in place and the old value is returned.
+    ///
+    /// # Complexity
+    /// One lookup in the index: `O(1)` for a [`KeyedPool`], `O(log n)` for an [`OrderedPoolMap`](crate::ordered_pool_map::OrderedPoolMap)
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> (ValueRef<(K, V)>, Option<V>) {
+        if let Some(reference) = self.index.position(&key) {
+            if let Some(pair) = self.pool.get_mut(reference) {
+                return (reference, Some(std::mem::replace(&mut pair.1, value)));
+            }
+        }
+        let reference = self.pool.push((key.clone(), value));
+        self.index.insert_position(key, reference);
+        (reference, None)
+    }
+
+    #[inline]
+    pub(crate) fn value_at(&self, reference: Option<ValueRef<(K, V)>>) -> Option<&V> {
+        self.pool.get(reference?).map(|(_, value)| value)
+    }
+
+    #[inline]
+    pub(crate) fn value_at_mut(&mut self, reference: Option<ValueRef<(K, V)>>) -> Option<&mut V> {
+        self.pool.get_mut(reference?).map(|(_, value)| value)
+    }
+
+    #[inline]
+    pub(crate) fn take_value(&mut self, reference: Option<ValueRef<(K, V)>>) -> Option<V> {
+        self.pool.take(reference?).map(|(_, value)| value)
+    }
+
+    /// Gets a borrow of the pair stored at `reference`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_by_ref(&self, reference: impl Into<ValueRef<(K, V)>>) -> Option<(&K, &V)> {
+        self.pool.get(reference).map(|(key, value)| (key, value))
+    }
+
+    /// Gets a borrow of the key and a mut borrow of the value stored at `reference`.
+    /// The key can't be changed, otherwise the index would be out of sync.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_mut_by_ref(
+        &mut self,
+        reference: impl Into<ValueRef<(K, V)>>,
+    ) -> Option<(&K, &mut V)> {
+        self.pool
+            .get_mut(reference)
+            .map(|(key, value)| (&*key, value))
+    }
+
+    /// Removes the pair stored at `reference` and returns it.
+    ///
+    /// # Complexity
+    /// One removal from the index: `O(1)` for a [`KeyedPool`], `O(log n)` for an [`OrderedPoolMap`](crate::ordered_pool_map::OrderedPoolMap)
+    #[inline]
+    pub fn remove_by_ref(&mut self, reference: impl Into<ValueRef<(K, V)>>) -> Option<(K, V)> {
+        let (key, value) = self.pool.take(reference)?;
+        self.index.remove_position(&key);
+        Some((key, value))
+    }
+
+    /// Removes all pairs.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.pool.clear();
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> KeyedPool<K, V> {
+    /// Creates a new, empty [`KeyedPool`].
+    #[inline]
+    pub fn new() -> KeyedPool<K, V> {
+        KeyedPool {
+            pool: (ValuePool::new()),
+            index: (HashMap::new()),
+        }
+    }
+
+    /// Creates a new [`KeyedPool`] that can store `capacity` many pairs.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> KeyedPool<K, V> {
+        KeyedPool {
+            pool: (ValuePool::with_capacity(capacity)),
+            index: (HashMap::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the position of the pair stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn ref_of<Q>(&self, key: &Q) -> Option<ValueRef<(K, V)>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(key).copied()
+    }
+
+    /// Returns true, if a pair is stored under `key`.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(key)
+    }
+
+    /// Gets a borrow of the value stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.value_at(self.ref_of(key))
+    }
+
+    /// Gets a mut borrow of the value stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.value_at_mut(self.ref_of(key))
+    }
+
+    /// Removes the pair stored under `key` and returns its value.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let reference = self.index.remove(key);
+        self.take_value(reference)
+    }
+
+    /// Iterates over all pairs in slot order.
+    /// ```
+    /// use value_pool::keyed_pool::KeyedPool;
+    ///
+    /// let mut pool = KeyedPool::new();
+    /// pool.insert('a', 1);
+    /// pool.insert('b', 2);
+    /// pool.insert('c', 3);
+    /// pool.remove(&'a');
+    /// pool.insert('d', 4); // reuses the position of 'a'
+    ///
+    /// let keys: Vec<char> = pool.iter().map(|(_, key, _)| *key).collect();
+    /// assert_eq!(keys, vec!['d', 'b', 'c']);
+    /// ```
+    ///
+    /// # Complexity
+    /// Be n = [`ValuePool::element_count()`] + [`ValuePool::waiting_positions()`] of the underlying pool.
+    /// `O(n)`
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ValueRef<(K, V)>, &K, &V)> {
+        self.pool
+            .occupied()
+            .map(|(reference, (key, value))| (reference, key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyedPool;
+
+    #[test]
+    fn test_index_stays_in_sync() {
+        let mut pool: KeyedPool<u32, &str> = KeyedPool::new();
+        let (one_ref, _) = pool.insert(1, "one");
+        let (two_ref, _) = pool.insert(2, "two");
+
+        assert_eq!(pool.remove_by_ref(one_ref), Some((1, "one")));
+        assert!(!pool.contains_key(&1));
+        assert_eq!(pool.ref_of(&2), Some(two_ref));
+
+        let (three_ref, _) = pool.insert(3, "three");
+        assert_eq!(three_ref, one_ref);
+        assert_eq!(pool.get(&3), Some(&"three"));
+        assert_eq!(pool.element_count(), 2);
+    }
+}
@@ -42,11 +42,72 @@
 //! ```
 //! # Features
 //! - *unsafe* - Library will use unsafe code to (potentially) improve speed. This could result in UB if implemented faulty even though it shouldn't and the behavior of your code should be unchanged.
+//! - *global* - Enables the `global_pool` module: one global pool per type for quick prototypes and tests.
+//! - *async* - Enables the `async_value_pool` module: a pool that can be shared between async tasks without blocking threads.
+//! - *paranoid* - Enables *unsafe*, but replaces its unchecked operations with checked ones again. Out of bounds calls of the `*_unchecked` methods return [`None`] instead of causing UB and are reported to the hook set with `ValuePool::set_out_of_bounds_hook`. Meant for testing code that uses *unsafe*.
+//! - *legacy-ordering* - Implements `PartialOrd` between [`ValueRef<T>`] and [`UntypedValueRef`]. Prefer [`ValueRef::slot_cmp`].
+//! - *introspection* - Adds `ValuePool::binary_layout`, a description of the memory layout of a pool for external tools like debuggers or memory profilers.
+//! - *metrics* - Adds `ValuePool::render_metrics`, which exports gauges like the element count in the Prometheus text format.
+//! - *slot-stats* - Counts how often every position gets reused, see `ValuePool::hottest_slots`. Useful to diagnose ABA-style bugs with stale [`ValueRef<T>`]s.
+//! - *test-util* - Enables the `soak` module: a soak-test harness that applies random operations to any `HandleContainer` and checks it against a shadow model.
+//! - *concurrency-checks* - Asserts internal invariants of the concurrent pools (e.g. `ReadMostlyPool<T>`) at runtime. Meant for your test suites, it costs some speed.
 #![warn(missing_docs)]
 
 use nonmax::NonMaxUsize;
-use std::{borrow::Borrow, hash::Hash, marker::PhantomData};
+use std::{
+    borrow::Borrow,
+    collections::TryReserveError,
+    fmt::Display,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{ControlFlow, Range},
+};
+#[cfg(feature = "async")]
+pub mod async_value_pool;
+pub mod batch;
+pub mod cache_padded;
+pub mod chunks;
+pub mod concurrent;
+pub mod dirty_pool;
+pub mod expiring_pool;
+pub mod fragmentation;
+pub mod gap_list;
+#[cfg(feature = "global")]
+pub mod global_pool;
+pub mod handle_container;
+#[cfg(feature = "introspection")]
+pub mod introspection;
+pub mod keyed_pool;
+pub mod list;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod multi_list;
+pub mod multi_size_pool;
+pub mod object_pool;
+pub mod observed_pool;
+pub mod occupancy;
+pub mod ordered_pool_map;
+pub mod pool;
+pub mod pool_set;
+pub mod pool_slice;
+pub mod pool_view;
+pub mod prelude;
+pub mod read_mostly_pool;
+pub mod ref_bi_map;
+pub mod ref_set;
+pub mod sequential_pool;
 pub mod smart_value_pool;
+#[cfg(feature = "test-util")]
+pub mod soak;
+pub mod steal_queue;
+mod sync;
+pub mod sync_value_ref;
+pub mod thread_local_pool;
+pub mod transaction;
+pub mod untyped_ref_map;
+pub mod versioned_pool;
+pub mod waitable_pool;
+pub mod zip;
 
 /// Struct that stores a location of an item in [`ValuePool<T>`]. It implements [`Copy`].
 ///
@@ -86,6 +147,57 @@ impl UntypedValueRef {
     pub fn new_non_max(index: NonMaxUsize) -> UntypedValueRef {
         UntypedValueRef { index: (index) }
     }
+
+    /// Compares the positions of both refs, independent of their types.
+    /// This replaces the `PartialOrd` impls between [`UntypedValueRef`] and [`ValueRef<T>`] (see the `legacy-ordering` feature).
+    /// It's only meaningful if both refs belong to the same [`ValuePool<T>`].
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use value_pool::{UntypedValueRef, ValueRef};
+    ///
+    /// let typed: ValueRef<u32> = ValueRef::new(3);
+    /// assert_eq!(UntypedValueRef::new(1).slot_cmp(typed), Ordering::Less);
+    /// ```
+    #[inline]
+    pub fn slot_cmp(&self, other: impl Into<UntypedValueRef>) -> std::cmp::Ordering {
+        self.index.cmp(&other.into().index)
+    }
+
+    /// Iterates over all refs from `range.start` up to (excluding) `range.end`, e.g. a contiguous block of positions.
+    /// ```
+    /// use value_pool::UntypedValueRef;
+    ///
+    /// let refs: Vec<usize> = UntypedValueRef::range(UntypedValueRef::new(2)..UntypedValueRef::new(5))
+    ///     .map(usize::from)
+    ///     .collect();
+    /// assert_eq!(refs, vec![2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn range(
+        range: Range<UntypedValueRef>,
+    ) -> impl DoubleEndedIterator<Item = UntypedValueRef> + ExactSizeIterator {
+        (range.start.index.get()..range.end.index.get()).map(UntypedValueRef::new)
+    }
+}
+
+impl TryFrom<usize> for UntypedValueRef {
+    type Error = MaxIndexError;
+
+    /// Same as [`UntypedValueRef::new`], but returns an error instead of panicking for [`usize::MAX`].
+    #[inline]
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        Ok(UntypedValueRef::new_non_max(
+            NonMaxUsize::new(index).ok_or(MaxIndexError)?,
+        ))
+    }
+}
+
+impl From<UntypedValueRef> for usize {
+    /// Returns the position of the ref.
+    #[inline]
+    fn from(value: UntypedValueRef) -> Self {
+        value.index.get()
+    }
 }
 
 impl Default for UntypedValueRef {
@@ -104,6 +216,8 @@ impl<T> PartialEq<ValueRef<T>> for UntypedValueRef {
     }
 }
 
+/// Only available with the `legacy-ordering` feature, use [`UntypedValueRef::slot_cmp`] instead.
+#[cfg(feature = "legacy-ordering")]
 impl<T> PartialOrd<ValueRef<T>> for UntypedValueRef {
     #[inline]
     fn partial_cmp(&self, other: &ValueRef<T>) -> Option<std::cmp::Ordering> {
@@ -119,6 +233,24 @@ impl<T> From<ValueRef<T>> for UntypedValueRef {
         }
     }
 }
+impl<T> TryFrom<usize> for ValueRef<T> {
+    type Error = MaxIndexError;
+
+    /// Same as [`ValueRef::new`], but returns an error instead of panicking for [`usize::MAX`].
+    #[inline]
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        Ok(UntypedValueRef::try_from(index)?.into())
+    }
+}
+
+impl<T> From<ValueRef<T>> for usize {
+    /// Returns the position of the ref.
+    #[inline]
+    fn from(value: ValueRef<T>) -> Self {
+        value.index.get()
+    }
+}
+
 impl<T> From<UntypedValueRef> for ValueRef<T> {
     #[inline]
     fn from(value: UntypedValueRef) -> Self {
@@ -155,10 +287,15 @@ impl<T> From<UntypedValueRef> for ValueRef<T> {
 /// let value_ref: ValueRef<u32> = ValueRef::new(4); // usually not needed or recommended
 /// assert_eq!(pool.get(value_ref), None); // Compiler error her, pool stores `usize`, but `value_ref` is `ValueRef<u32>`
 /// ```
+///
+/// # Thread safety
+/// A [`ValueRef<T>`] only stores a position, never a `T`. It is [`Send`] and [`Sync`] for every `T`,
+/// even if `T` itself is neither (e.g. [`Rc`](std::rc::Rc)).
 #[derive(Debug)]
 pub struct ValueRef<T> {
     index: NonMaxUsize,
-    type_info: PhantomData<T>,
+    // `fn() -> T` keeps `ValueRef<T>` `Send` and `Sync` independent of `T`
+    type_info: PhantomData<fn() -> T>,
 }
 
 impl<T> PartialEq<UntypedValueRef> for ValueRef<T> {
@@ -167,6 +304,8 @@ impl<T> PartialEq<UntypedValueRef> for ValueRef<T> {
         self.index == other.index
     }
 }
+/// Only available with the `legacy-ordering` feature, use [`ValueRef::slot_cmp`] instead.
+#[cfg(feature = "legacy-ordering")]
 impl<T> PartialOrd<UntypedValueRef> for ValueRef<T> {
     #[inline]
     fn partial_cmp(&self, other: &UntypedValueRef) -> Option<std::cmp::Ordering> {
@@ -252,6 +391,39 @@ impl<T> ValueRef<T> {
             type_info: (PhantomData),
         }
     }
+
+    /// Compares the positions of both refs, see [`UntypedValueRef::slot_cmp`].
+    #[inline]
+    pub fn slot_cmp(&self, other: impl Into<UntypedValueRef>) -> std::cmp::Ordering {
+        UntypedValueRef::from(*self).slot_cmp(other)
+    }
+
+    /// Encodes this [`ValueRef<T>`] into a single [`u64`], e.g. to embed it into an existing entity id scheme.
+    /// The index is stored in the low 32 bits, the high 32 bits are reserved for a generation and are currently always `0`.
+    /// This is the layout many ECS crates use for their entity ids.
+    /// Returns [`None`] if the index doesn't fit into 32 bits, i.e. `index > u32::MAX`.
+    /// ```
+    /// use value_pool::ValueRef;
+    ///
+    /// let reference: ValueRef<u32> = ValueRef::new(7);
+    /// assert_eq!(reference.try_to_bits(), Some(7));
+    /// assert_eq!(ValueRef::from_bits(reference.try_to_bits().unwrap()), Some(reference));
+    /// ```
+    #[inline]
+    pub fn try_to_bits(&self) -> Option<u64> {
+        u32::try_from(self.index.get()).ok().map(u64::from)
+    }
+
+    /// Decodes a [`ValueRef<T>`] from bits created by [`ValueRef::try_to_bits`].
+    /// Returns [`None`] if the generation bits (the high 32 bits) aren't `0`, or the index isn't a valid position.
+    #[inline]
+    pub fn from_bits(bits: u64) -> Option<ValueRef<T>> {
+        if bits >> 32 != 0 {
+            return None;
+        }
+        let index = usize::try_from(bits).ok()?;
+        Some(ValueRef::new_nonmax(NonMaxUsize::new(index)?))
+    }
 }
 
 impl<T> PartialEq for ValueRef<T> {
@@ -262,6 +434,155 @@ impl<T> PartialEq for ValueRef<T> {
 }
 impl<T> Eq for ValueRef<T> {}
 
+/// Describes how a [`ValuePool<T>`] grows its storage once it is full. Set with [`ValuePool::set_growth_strategy`].
+/// ```
+/// use value_pool::{GrowthStrategy, ValuePool};
+///
+/// let mut pool: ValuePool<[u8; 4096]> = ValuePool::with_capacity(4);
+/// pool.set_growth_strategy(GrowthStrategy::Fixed(4));
+/// for _ in 0..5 {
+///     pool.push([0; 4096]);
+/// }
+/// assert_eq!(pool.capacity(), 8);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub enum GrowthStrategy {
+    /// Let the underlying [`Vec`] decide, which currently doubles the capacity. This is the default.
+    #[default]
+    Double,
+    /// Grow the capacity by 50%.
+    OneAndHalf,
+    /// Grow the capacity by the given number of items (at least one).
+    Fixed(usize),
+    /// Call the function with the current capacity and grow to the returned capacity (at least by one).
+    Custom(fn(usize) -> usize),
+}
+
+impl GrowthStrategy {
+    /// Returns the capacity a full storage with `capacity` should grow to.
+    #[inline]
+    fn next_capacity(&self, capacity: usize) -> usize {
+        let next = match self {
+            GrowthStrategy::Double => capacity.saturating_mul(2),
+            GrowthStrategy::OneAndHalf => capacity.saturating_add(capacity / 2),
+            GrowthStrategy::Fixed(step) => capacity.saturating_add(*step),
+            GrowthStrategy::Custom(f) => f(capacity),
+        };
+        next.max(capacity.saturating_add(1))
+    }
+}
+
+/// Returned by [`ValuePool::try_take`] and [`ValuePool::try_remove`] if nothing was removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotError {
+    /// No item is stored at the position.
+    Empty,
+    /// The position is pinned, see [`ValuePool::pin_slot`].
+    Pinned,
+}
+
+impl Display for SlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlotError::Empty => f.write_str("no item is stored at this position"),
+            SlotError::Pinned => f.write_str("the position is pinned and can't be removed"),
+        }
+    }
+}
+
+impl std::error::Error for SlotError {}
+
+/// Returned when converting [`usize::MAX`] into an [`UntypedValueRef`] or [`ValueRef<T>`], it's no valid position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaxIndexError;
+
+impl Display for MaxIndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("usize::MAX is no valid position")
+    }
+}
+
+impl std::error::Error for MaxIndexError {}
+
+/// The errors of this crate in one type, so fallible calls of different APIs can be combined with `?`.
+/// All other error types of this crate convert into it.
+/// ```
+/// use value_pool::{Error, ValuePool, ValueRef};
+///
+/// fn move_value(pool: &mut ValuePool<u32>, from: ValueRef<u32>) -> Result<ValueRef<u32>, Error> {
+///     let value = pool.try_take(from)?;
+///     pool.try_reserve(1)?;
+///     Ok(pool.push(value))
+/// }
+///
+/// let mut pool: ValuePool<u32> = ValuePool::new();
+/// let first = pool.push(1);
+/// assert!(move_value(&mut pool, first).is_ok());
+/// assert_eq!(pool.try_get(ValueRef::new(5)), Err(Error::OutOfBounds));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The position is not part of the pool.
+    OutOfBounds,
+    /// No item is stored at the position.
+    Vacant,
+    /// The position is pinned, see [`ValuePool::pin_slot`].
+    Pinned,
+    /// The capacity couldn't grow, see [`ValuePool::try_reserve`].
+    CapacityExceeded,
+    /// [`usize::MAX`] is no valid position, see [`MaxIndexError`].
+    MaxIndex,
+    /// A callback of a [`SmartValuePool`](smart_value_pool::SmartValuePool) panicked, see [`PoisonError`](smart_value_pool::PoisonError).
+    Poisoned,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OutOfBounds => f.write_str("the position is out of bounds"),
+            Error::Vacant => SlotError::Empty.fmt(f),
+            Error::Pinned => SlotError::Pinned.fmt(f),
+            Error::CapacityExceeded => f.write_str("the capacity couldn't grow"),
+            Error::MaxIndex => MaxIndexError.fmt(f),
+            Error::Poisoned => smart_value_pool::PoisonError.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<SlotError> for Error {
+    #[inline]
+    fn from(value: SlotError) -> Self {
+        match value {
+            SlotError::Empty => Error::Vacant,
+            SlotError::Pinned => Error::Pinned,
+        }
+    }
+}
+
+impl From<MaxIndexError> for Error {
+    #[inline]
+    fn from(_: MaxIndexError) -> Self {
+        Error::MaxIndex
+    }
+}
+
+impl From<smart_value_pool::PoisonError> for Error {
+    #[inline]
+    fn from(_: smart_value_pool::PoisonError) -> Self {
+        Error::Poisoned
+    }
+}
+
+impl From<TryReserveError> for Error {
+    #[inline]
+    fn from(_: TryReserveError) -> Self {
+        Error::CapacityExceeded
+    }
+}
+
 // TODO: use SmallVec (as a feature) when it hits v2 (https://github.com/servo/rust-smallvec/tree/v2)
 
 /// A [`ValuePool<T>`] allows referencing data stored within without a lifetime bound.  
@@ -278,10 +599,25 @@ impl<T> Eq for ValueRef<T> {}
 /// assert_eq!(minus_ten, Some(-10i32));
 /// assert_eq!(pool.get(minus_ten_ref), None);
 /// ```
+///
+/// # Thread safety
+/// [`ValuePool<T>`] is [`Send`] if `T` is [`Send`] and [`Sync`] if `T` is [`Sync`], just like [`Vec<T>`].
+/// To share one between threads, wrap it in a lock, for example with [`SyncValueRef<T>`](crate::sync_value_ref::SyncValueRef).
 #[derive(Debug, Clone)]
 pub struct ValuePool<T> {
     store: Vec<Option<T>>,
     open_indices: Vec<NonMaxUsize>,
+    growth: GrowthStrategy,
+    // sorted positions that can't be taken or removed
+    pinned: Vec<NonMaxUsize>,
+    // how often each position got reused by `push`, by index
+    #[cfg(feature = "slot-stats")]
+    reuse_counts: Vec<u64>,
+    // called with the old and new capacity after the store grew
+    growth_hook: Option<fn(usize, usize)>,
+    // called with the method, index and length of out of bounds `*_unchecked` calls
+    #[cfg(feature = "paranoid")]
+    out_of_bounds_hook: Option<fn(&'static str, usize, usize)>,
 }
 
 impl<T> Default for ValuePool<T> {
@@ -297,14 +633,37 @@ impl<T> ValuePool<T> {
         ValuePool {
             store: (Vec::with_capacity(capacity)),
             open_indices: (Vec::with_capacity(capacity / 4)),
+            growth: (GrowthStrategy::Double),
+            pinned: (Vec::new()),
+            #[cfg(feature = "slot-stats")]
+            reuse_counts: (Vec::new()),
+            growth_hook: None,
+            #[cfg(feature = "paranoid")]
+            out_of_bounds_hook: None,
         }
     }
-    /// Creates a new, empty [`ValuePool`].
+    /// Creates a new, empty [`ValuePool`]. This doesn't allocate and can be used in `static`s:
+    /// ```
+    /// use std::sync::Mutex;
+    /// use value_pool::ValuePool;
+    ///
+    /// static POOL: Mutex<ValuePool<u32>> = Mutex::new(ValuePool::new());
+    ///
+    /// let reference = POOL.lock().unwrap().push(3);
+    /// assert_eq!(POOL.lock().unwrap().get(reference), Some(&3));
+    /// ```
     #[inline]
-    pub fn new() -> ValuePool<T> {
+    pub const fn new() -> ValuePool<T> {
         ValuePool {
             store: (Vec::new()),
             open_indices: (Vec::new()),
+            growth: (GrowthStrategy::Double),
+            pinned: (Vec::new()),
+            #[cfg(feature = "slot-stats")]
+            reuse_counts: (Vec::new()),
+            growth_hook: None,
+            #[cfg(feature = "paranoid")]
+            out_of_bounds_hook: None,
         }
     }
 
@@ -314,6 +673,12 @@ impl<T> ValuePool<T> {
         self.store.len() - self.open_indices.len()
     }
 
+    /// Same as [`ValuePool::element_count`], named like [`Vec::len`] for code written against standard containers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.element_count()
+    }
+
     /// Returns true if any `T`s are stored. Equivalent to: [`ValuePool::element_count() == 0`](ValuePool::element_count()).
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -326,6 +691,96 @@ impl<T> ValuePool<T> {
         self.store.capacity()
     }
 
+    /// Returns the [`GrowthStrategy`] used once the storage is full.
+    #[inline]
+    pub fn growth_strategy(&self) -> GrowthStrategy {
+        self.growth
+    }
+
+    /// Sets the [`GrowthStrategy`] used once the storage is full.
+    #[inline]
+    pub fn set_growth_strategy(&mut self, strategy: GrowthStrategy) {
+        self.growth = strategy;
+    }
+
+    /// Sets a function that gets called with the old and the new capacity whenever the storage of this [`ValuePool`] grows,
+    /// by [`ValuePool::push`] or by reserving. It's meant to detect unexpected allocations in release builds, e.g. of an audio
+    /// or game loop, and costs one comparison per push. Clones of this pool keep the hook.
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use value_pool::ValuePool;
+    ///
+    /// static GROWTHS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::with_capacity(2);
+    /// pool.set_growth_hook(Some(|_old, _new| {
+    ///     GROWTHS.fetch_add(1, Ordering::Relaxed);
+    /// }));
+    /// pool.push(1);
+    /// pool.push(2);
+    /// assert_eq!(GROWTHS.load(Ordering::Relaxed), 0);
+    /// pool.push(3);
+    /// assert_eq!(GROWTHS.load(Ordering::Relaxed), 1);
+    /// ```
+    #[inline]
+    pub fn set_growth_hook(&mut self, hook: Option<fn(usize, usize)>) {
+        self.growth_hook = hook;
+    }
+
+    /// Returns the function set with [`ValuePool::set_growth_hook`].
+    #[inline]
+    pub fn growth_hook(&self) -> Option<fn(usize, usize)> {
+        self.growth_hook
+    }
+
+    /// Sets a function that gets called by the `*_unchecked` methods with the method name, the index and the length of the storage
+    /// when they are used with an out of bounds reference. Those calls return [`None`] either way, the hook only reports them,
+    /// e.g. by logging or panicking. Clones of this pool keep the hook.
+    /// ```
+    /// use value_pool::{ValuePool, ValueRef};
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// pool.set_out_of_bounds_hook(Some(|method, index, len| {
+    ///     panic!("ValuePool::{method} called with out of bounds index {index} (length {len})");
+    /// }));
+    /// let result = std::panic::catch_unwind(|| unsafe { pool.get_unchecked(ValueRef::new(3)).copied() });
+    /// assert!(result.is_err());
+    /// ```
+    #[cfg(feature = "paranoid")]
+    #[inline]
+    pub fn set_out_of_bounds_hook(&mut self, hook: Option<fn(&'static str, usize, usize)>) {
+        self.out_of_bounds_hook = hook;
+    }
+
+    /// Returns the function set with [`ValuePool::set_out_of_bounds_hook`].
+    #[cfg(feature = "paranoid")]
+    #[inline]
+    pub fn out_of_bounds_hook(&self) -> Option<fn(&'static str, usize, usize)> {
+        self.out_of_bounds_hook
+    }
+
+    /// Calls the growth hook if the capacity changed from `old_capacity`.
+    #[inline]
+    fn report_growth(&self, old_capacity: usize) {
+        if let Some(hook) = self.growth_hook {
+            let new_capacity = self.store.capacity();
+            if new_capacity != old_capacity {
+                hook(old_capacity, new_capacity);
+            }
+        }
+    }
+
+    /// Grows the storage according to `self.growth` if no more items fit.
+    #[inline]
+    fn grow_if_full(&mut self) {
+        if self.store.len() < self.store.capacity() || matches!(self.growth, GrowthStrategy::Double)
+        {
+            return;
+        }
+        let new_capacity = self.growth.next_capacity(self.store.capacity());
+        self.store.reserve_exact(new_capacity - self.store.len());
+    }
+
     /// Returns the number of positions that are currently empty. These positions are prioritized when pushing new values.
     #[inline]
     pub fn waiting_positions(&self) -> usize {
@@ -343,6 +798,29 @@ impl<T> ValuePool<T> {
         reference.index.get() < self.store.len()
     }
 
+    /// Checks the precondition of the `*_unchecked` methods: In debug builds, an out of bounds `reference` panics.
+    /// With the `paranoid` feature, it gets reported to the [out of bounds hook](ValuePool::set_out_of_bounds_hook) and false is returned,
+    /// so the caller can bail out instead of causing UB.
+    #[inline]
+    #[track_caller]
+    fn check_unchecked_access(&self, method: &'static str, reference: ValueRef<T>) -> bool {
+        let in_bounce = self.is_ref_in_bounce(reference);
+        #[cfg(feature = "paranoid")]
+        if !in_bounce {
+            if let Some(hook) = self.out_of_bounds_hook {
+                hook(method, reference.index.get(), self.store.len());
+            }
+            return false;
+        }
+        debug_assert!(
+            in_bounce,
+            "ValuePool::{method} called with out of bounds index {} (length {})",
+            reference.index.get(),
+            self.store.len()
+        );
+        true
+    }
+
     /// Pushes a new value into the [`ValuePool`] and returns a [`ValueRef<T>`] (that stores its position).
     /// You can access this value with `get`.
     ///
@@ -350,62 +828,117 @@ impl<T> ValuePool<T> {
     /// `O(1)`
     #[inline]
     pub fn push(&mut self, value: T) -> ValueRef<T> {
-        if !self.open_indices.is_empty() {
-            let index = self.open_indices.pop().unwrap();
+        if let Some(index) = self.open_indices.pop() {
+            #[cfg(feature = "slot-stats")]
+            self.count_reuse(index.get());
             self.store[index.get()] = Some(value);
             ValueRef::new_nonmax(index)
         } else {
+            let capacity = self.store.capacity();
+            self.grow_if_full();
             self.store.push(Some(value));
+            self.report_growth(capacity);
+            #[cfg(feature = "slot-stats")]
+            self.count_append(self.store.len() - 1);
             ValueRef::new(self.store.len() - 1)
         }
     }
 
-    /// Removes an item from [`ValuePool`].  
+    /// Same as [`ValuePool::push`], but never allocates: if no position is empty and the capacity is used up,
+    /// `value` is given back. Together with [`ValuePool::try_reserve`] this allows using a pool where panics and
+    /// allocations are forbidden, e.g. on a real-time audio thread.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<f32> = ValuePool::with_capacity(1);
+    /// let sample = pool.push_within_capacity(0.5).unwrap();
+    /// assert_eq!(pool.push_within_capacity(1.0), Err(1.0));
+    ///
+    /// pool.take(sample);
+    /// assert!(pool.push_within_capacity(1.0).is_ok());
+    ///
+    /// pool.try_reserve(64).unwrap();
+    /// assert!(pool.push_within_capacity(2.0).is_ok());
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push_within_capacity(&mut self, value: T) -> Result<ValueRef<T>, T> {
+        if self.open_indices.is_empty() && self.store.len() == self.store.capacity() {
+            return Err(value);
+        }
+        Ok(self.push(value))
+    }
+
+    /// Same as [`ValuePool::push`], named like the insertion methods of standard containers.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn insert(&mut self, value: T) -> ValueRef<T> {
+        self.push(value)
+    }
+
+    /// Removes an item from [`ValuePool`]. Pinned items are not removed, see [`ValuePool::try_remove`].  
     /// If this item is stored last its position won't be marked empty but instead the underlying  
     /// data structure will be reduced in length.  
     /// Note: This will **not** reduce the used memory of this [`ValuePool<T>`].
     ///
+    /// Returns the removed item, like [`ValuePool::take`].
+    ///
     /// # Complexity
     /// `O(1)`
     #[inline]
-    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) {
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
         let reference: ValueRef<T> = reference.into();
-        if !self.has_item(reference) {
-            return;
+        if !self.has_item(reference) || self.is_pinned(reference) {
+            return None;
         }
         // => there is an item at reference
 
         // if `reference` is the last index and has a value; this can prevent reallocation of `self.open_indices`
         if reference.index.get() + 1 == self.store.len() {
-            self.store.pop();
-            return;
+            return self.store.pop().flatten();
         }
 
-        #[cfg(feature = "unsafe")]
+        #[cfg(all(feature = "unsafe", not(feature = "paranoid")))]
         unsafe {
             // value must exist cause `self.has_item` is true
+            debug_assert!(self.is_ref_in_bounce(reference));
             let value = self.store.get_unchecked_mut(reference.index.get());
             self.open_indices.push(reference.index);
-            *value = None
+            value.take()
         }
-        #[cfg(not(feature = "unsafe"))]
+        #[cfg(any(not(feature = "unsafe"), feature = "paranoid"))]
         {
             // value must exist cause `self.has_item` is true
             let value = self.store.get_mut(reference.index.get()).unwrap();
             self.open_indices.push(reference.index);
-            *value = None;
+            value.take()
         }
     }
 
     /// # Safety
     /// Makes the greatest [`ValueRef<T>`] point to the wrong (actually now [`None`]) element.
     /// This function will not panic or create UB.
+    /// Pinned items are not removed; if the greatest item is pinned, its pin moves with it.
     ///
     /// # Complexity
-    /// `O(1)`
+    /// `O(1)` if nothing is pinned
     #[inline]
     pub unsafe fn remove_full(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
         let reference: ValueRef<T> = reference.into();
+        if self.is_pinned(reference) {
+            return None;
+        }
+        let last = self.store.len().checked_sub(1).map(ValueRef::<T>::new);
+        if last.is_some_and(|last| self.unpin_slot(last)) {
+            let position = self
+                .pinned
+                .partition_point(|index| *index < reference.index);
+            self.pinned.insert(position, reference.index);
+        }
         self.store.swap_remove(reference.index.get())
     }
 
@@ -421,16 +954,93 @@ impl<T> ValuePool<T> {
             .and_then(|x| x.as_ref())
     }
 
+    /// Same as [`ValuePool::get`], but returns why nothing was found: [`Error::OutOfBounds`] or [`Error::Vacant`].
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn try_get(&self, reference: impl Into<ValueRef<T>>) -> Result<&T, Error> {
+        let reference: ValueRef<T> = reference.into();
+        self.store
+            .get(reference.index.get())
+            .ok_or(Error::OutOfBounds)?
+            .as_ref()
+            .ok_or(Error::Vacant)
+    }
+
+    /// Gets borrows of the items at `ref_1` and `ref_2` in one call, e.g. for parent/child lookups.
+    /// The refs may be equal.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let parent = pool.push("parent");
+    /// let child = pool.push("child");
+    /// pool.take(child);
+    ///
+    /// assert_eq!(pool.get2(parent, parent), (Some(&"parent"), Some(&"parent")));
+    /// assert_eq!(pool.get2(parent, child), (Some(&"parent"), None));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get2(
+        &self,
+        ref_1: impl Into<ValueRef<T>>,
+        ref_2: impl Into<ValueRef<T>>,
+    ) -> (Option<&T>, Option<&T>) {
+        (self.get(ref_1), self.get(ref_2))
+    }
+
+    /// Same as [`ValuePool::get2`], for three refs.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get3(
+        &self,
+        ref_1: impl Into<ValueRef<T>>,
+        ref_2: impl Into<ValueRef<T>>,
+        ref_3: impl Into<ValueRef<T>>,
+    ) -> (Option<&T>, Option<&T>, Option<&T>) {
+        (self.get(ref_1), self.get(ref_2), self.get(ref_3))
+    }
+
+    /// Same as [`ValuePool::get2`], for four refs.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get4(
+        &self,
+        ref_1: impl Into<ValueRef<T>>,
+        ref_2: impl Into<ValueRef<T>>,
+        ref_3: impl Into<ValueRef<T>>,
+        ref_4: impl Into<ValueRef<T>>,
+    ) -> (Option<&T>, Option<&T>, Option<&T>, Option<&T>) {
+        (
+            self.get(ref_1),
+            self.get(ref_2),
+            self.get(ref_3),
+            self.get(ref_4),
+        )
+    }
+
     /// Gets a borrow of the item pointed to by `reference` if an item is stored there.
     ///
     /// # Safety
     /// Calling this method with an `reference` that is out of bounds, is UB. You can check beforehand with [`ValuePool::is_ref_in_bounce`].
+    /// Debug builds panic instead, with the `paranoid` feature [`None`] is returned.
     ///
     /// # Complexity
     /// `O(1)`
     #[inline]
     pub unsafe fn get_unchecked(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
         let reference: ValueRef<T> = reference.into();
+        if !self.check_unchecked_access("get_unchecked", reference) {
+            return None;
+        }
         self.store.get_unchecked(reference.index.get()).as_ref()
     }
 
@@ -446,10 +1056,25 @@ impl<T> ValuePool<T> {
             .and_then(|x| x.as_mut())
     }
 
+    /// Same as [`ValuePool::get_mut`], but returns why nothing was found: [`Error::OutOfBounds`] or [`Error::Vacant`].
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn try_get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Result<&mut T, Error> {
+        let reference: ValueRef<T> = reference.into();
+        self.store
+            .get_mut(reference.index.get())
+            .ok_or(Error::OutOfBounds)?
+            .as_mut()
+            .ok_or(Error::Vacant)
+    }
+
     /// Gets a mut borrow of the item pointed to by `reference` if an item is stored there.
     ///
     /// # Safety
     /// Calling this method with an reference that is out of bounds, is UB. You can check beforehand with [`ValuePool::is_ref_in_bounce`].
+    /// Debug builds panic instead, with the `paranoid` feature [`None`] is returned.
     ///
     /// # Complexity
     /// `O(1)`
@@ -459,16 +1084,21 @@ impl<T> ValuePool<T> {
         reference: impl Into<ValueRef<T>>,
     ) -> Option<&mut T> {
         let reference: ValueRef<T> = reference.into();
+        if !self.check_unchecked_access("get_unchecked_mut", reference) {
+            return None;
+        }
         self.store.get_unchecked_mut(reference.index.get()).as_mut()
     }
 
     /// Swaps `ref_1` with `ref_2`, all other refs equal two the both will point to the wrong element.
     ///
+    /// Returns [`None`] if one of them is out of bounds or [pinned](ValuePool::pin_slot), as a pinned item must stay in place.
+    ///
     /// # Note
     /// All other references equal to `ref_1` or `ref_2` now point to the wrong element.
     ///
     /// # Complexity
-    /// `O(1)`
+    /// `O(1)`, `O(log p)` if positions are pinned, where `p` is the number of pinned positions
     #[inline]
     pub fn swap(
         &mut self,
@@ -479,10 +1109,47 @@ impl<T> ValuePool<T> {
         if ref_1.index.get() >= self.store.len() || ref_2.index.get() >= self.store.len() {
             return None;
         }
+        if self.is_pinned(ref_1) || self.is_pinned(ref_2) {
+            return None;
+        }
         self.store.swap(ref_1.index.get(), ref_2.index.get());
         Some((ref_2, ref_1))
     }
 
+    /// Same as [`ValuePool::swap`], but calls `fixup(ref_1, ref_2)` right after the swap happened,
+    /// so external mappings from or to [`ValueRef<T>`]s can be updated in the same step.
+    /// `fixup` is not called if the swap failed.
+    /// ```
+    /// use value_pool::{ValuePool, ref_bi_map::RefBiMap};
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let mut names: ValuePool<&str> = ValuePool::new();
+    /// let mut links: RefBiMap<&str, &str> = RefBiMap::new();
+    ///
+    /// let a = pool.push("a");
+    /// let b = pool.push("b");
+    /// links.insert(a, names.push("name of a"));
+    ///
+    /// pool.swap_tracked(a, b, |ref_1, ref_2| links.swap_left(ref_1, ref_2));
+    /// assert_eq!(pool.get(b), Some(&"a"));
+    /// assert_eq!(names.get(links.get_right(b).unwrap()), Some(&"name of a"));
+    /// assert_eq!(links.get_right(a), None);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(1)` + complexity of `fixup`
+    #[inline]
+    pub fn swap_tracked(
+        &mut self,
+        ref_1: impl Into<ValueRef<T>>,
+        ref_2: impl Into<ValueRef<T>>,
+        fixup: impl FnOnce(ValueRef<T>, ValueRef<T>),
+    ) -> Option<(ValueRef<T>, ValueRef<T>)> {
+        let (ref_2, ref_1) = self.swap(ref_1, ref_2)?;
+        fixup(ref_1, ref_2);
+        Some((ref_2, ref_1))
+    }
+
     /// Returns the value_ref value the next call to [`ValuePool::push`] would return.
     ///
     /// # Complexity
@@ -492,17 +1159,19 @@ impl<T> ValuePool<T> {
         if self.open_indices.is_empty() {
             return ValueRef::new(self.store.len());
         }
-        #[cfg(feature = "unsafe")]
+        #[cfg(all(feature = "unsafe", not(feature = "paranoid")))]
         unsafe {
-            return ValueRef::new_nonmax(*self.open_indices.last().unwrap_unchecked());
+            debug_assert!(!self.open_indices.is_empty());
+            ValueRef::new_nonmax(*self.open_indices.last().unwrap_unchecked())
         }
-        #[cfg(not(feature = "unsafe"))]
+        #[cfg(any(not(feature = "unsafe"), feature = "paranoid"))]
         {
-            return ValueRef::new_nonmax(*self.open_indices.last().unwrap());
+            ValueRef::new_nonmax(*self.open_indices.last().unwrap())
         }
     }
 
     /// Takes value at `reference` and returns it. Calling it again with the same `reference` _(without modifying this [`ValuePool<T>`])_ will always return [`None`].  
+    /// Pinned items are not taken, see [`ValuePool::try_take`].
     /// ```
     /// use value_pool::ValuePool;
     /// let mut pool: ValuePool<usize> = ValuePool::new();
@@ -521,6 +1190,9 @@ impl<T> ValuePool<T> {
     pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
         let mut tmp = None;
         let reference: ValueRef<T> = reference.into();
+        if self.is_pinned(reference) {
+            return None;
+        }
         std::mem::swap(&mut tmp, self.store.get_mut(reference.index.get())?);
         if tmp.is_some() {
             // if tmp is none, reference.index should already be in self.open_indices
@@ -533,6 +1205,7 @@ impl<T> ValuePool<T> {
     ///
     /// # Safety
     /// Calling this method with an reference that is out of bounds, is UB. You can check beforehand with [`ValuePool::is_ref_in_bounce`].
+    /// Debug builds panic instead, with the `paranoid` feature [`None`] is returned.
     /// ```
     /// use value_pool::ValuePool;
     /// let mut pool: ValuePool<usize> = ValuePool::new();
@@ -551,6 +1224,9 @@ impl<T> ValuePool<T> {
     pub unsafe fn take_unchecked(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
         let mut tmp = None;
         let reference: ValueRef<T> = reference.into();
+        if self.is_pinned(reference) || !self.check_unchecked_access("take_unchecked", reference) {
+            return None;
+        }
         std::mem::swap(
             &mut tmp,
             self.store.get_unchecked_mut(reference.index.get()),
@@ -563,7 +1239,7 @@ impl<T> ValuePool<T> {
     }
 
     /// Replaces the struct at `reference` with `value`.
-    /// If reference is out of bounds: returns `Err(value)`.
+    /// If reference is out of bounds, or `value` is [`None`] and `reference` is [pinned](ValuePool::pin_slot): returns `Err(value)`.
     /// Else: returns `Ok(struct at reference)`
     ///
     /// # Example
@@ -596,6 +1272,9 @@ impl<T> ValuePool<T> {
         mut value: Option<T>,
     ) -> Result<Option<T>, Option<T>> {
         let reference: ValueRef<T> = reference.into();
+        if value.is_none() && self.is_pinned(reference) {
+            return Err(value);
+        }
         let accessed_value = self.store.get_mut(reference.index.get());
         match accessed_value {
             None => Err(value),
@@ -606,10 +1285,112 @@ impl<T> ValuePool<T> {
         }
     }
 
+    /// Pins the item at `reference`, so [`ValuePool::take`], [`ValuePool::remove`], [`ValuePool::swap`] and [`ValuePool::replace`] with [`None`]
+    /// leave it in place until it's [unpinned](ValuePool::unpin_slot).
+    /// Returns false if no item is stored at `reference`.
+    /// ```
+    /// use value_pool::{SlotError, ValuePool};
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let config = pool.push("shared config");
+    /// pool.pin_slot(config);
+    ///
+    /// assert_eq!(pool.take(config), None);
+    /// assert_eq!(pool.try_take(config), Err(SlotError::Pinned));
+    ///
+    /// pool.unpin_slot(config);
+    /// assert_eq!(pool.try_take(config), Ok("shared config"));
+    /// ```
+    /// Note: [`ValuePool::clear`] removes pinned items and their pins as well.
+    ///
+    /// # Complexity
+    /// `O(p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn pin_slot(&mut self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        if !self.has_item(reference) {
+            return false;
+        }
+        if let Err(position) = self.pinned.binary_search(&reference.index) {
+            self.pinned.insert(position, reference.index);
+        }
+        true
+    }
+
+    /// Unpins the position `reference`. Returns false if it wasn't pinned.
+    ///
+    /// # Complexity
+    /// `O(p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn unpin_slot(&mut self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        match self.pinned.binary_search(&reference.index) {
+            Ok(position) => {
+                self.pinned.remove(position);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if the position `reference` is pinned.
+    ///
+    /// # Complexity
+    /// `O(log p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn is_pinned(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        !self.pinned.is_empty() && self.pinned.binary_search(&reference.index).is_ok()
+    }
+
+    /// Same as [`ValuePool::take`], but returns why nothing was taken.
+    ///
+    /// # Complexity
+    /// `O(log p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn try_take(&mut self, reference: impl Into<ValueRef<T>>) -> Result<T, SlotError> {
+        let reference: ValueRef<T> = reference.into();
+        if self.is_pinned(reference) {
+            return Err(SlotError::Pinned);
+        }
+        self.take(reference).ok_or(SlotError::Empty)
+    }
+
+    /// Same as [`ValuePool::remove`], but returns why nothing was removed.
+    ///
+    /// # Complexity
+    /// `O(log p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn try_remove(&mut self, reference: impl Into<ValueRef<T>>) -> Result<T, SlotError> {
+        let reference: ValueRef<T> = reference.into();
+        if self.is_pinned(reference) {
+            return Err(SlotError::Pinned);
+        }
+        self.remove(reference).ok_or(SlotError::Empty)
+    }
+
     /// Ensures at least `additional` elements can be stored without additional reallocations.
+    /// If the [`GrowthStrategy`] is not [`GrowthStrategy::Double`], no more than `additional` elements are reserved.
     #[inline]
     pub fn reserve(&mut self, additional: usize) {
-        self.store.reserve(additional);
+        let capacity = self.store.capacity();
+        match self.growth {
+            GrowthStrategy::Double => self.store.reserve(additional),
+            _ => self.store.reserve_exact(additional),
+        }
+        self.report_growth(capacity);
+    }
+
+    /// Same as [`ValuePool::reserve`], but returns an error instead of panicking if the capacity overflows or the allocation fails.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let capacity = self.store.capacity();
+        match self.growth {
+            GrowthStrategy::Double => self.store.try_reserve(additional)?,
+            _ => self.store.try_reserve_exact(additional)?,
+        }
+        self.report_growth(capacity);
+        Ok(())
     }
 
     /// Returns true, if an item is stored at `reference`.
@@ -653,7 +1434,172 @@ impl<T> ValuePool<T> {
         })?))
     }
 
-    /// Clears this [`ValuePool<T>`].
+    /// Same as [`ValuePool::find`], but faster for small [`Copy`] types like integers.
+    /// Positions are compared in blocks of 8 without branching inside a block, which the compiler can vectorize.
+    /// ```
+    /// use value_pool::{ValuePool, ValueRef};
+    ///
+    /// let mut pool: ValuePool<u16> = ValuePool::new();
+    /// for value in 0..100 {
+    ///     pool.push(value);
+    /// }
+    /// pool.take(ValueRef::new(42));
+    /// assert_eq!(pool.find_fast(42), None);
+    /// assert_eq!(pool.find_fast(97), Some(ValueRef::new(97)));
+    /// ```
+    ///
+    /// # Complexity
+    /// Same as [`ValuePool::find`]
+    pub fn find_fast(&self, value: T) -> Option<ValueRef<T>>
+    where
+        T: Copy + Eq,
+    {
+        const LANES: usize = 8;
+        let needle = Some(value);
+        let mut blocks = self.store.chunks_exact(LANES);
+        for (block_index, block) in blocks.by_ref().enumerate() {
+            let mut matches = 0u8;
+            for (lane, slot) in block.iter().enumerate() {
+                matches |= ((*slot == needle) as u8) << lane;
+            }
+            if matches != 0 {
+                return Some(ValueRef::new(
+                    block_index * LANES + matches.trailing_zeros() as usize,
+                ));
+            }
+        }
+        let rest_start = self.store.len() - blocks.remainder().len();
+        let position = blocks.remainder().iter().position(|slot| *slot == needle)?;
+        Some(ValueRef::new(rest_start + position))
+    }
+
+    /// Returns the number of stored items for which `predicate` returns true.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let taken = pool.push(2);
+    /// pool.push(4);
+    /// pool.push(5);
+    /// pool.take(taken);
+    ///
+    /// assert_eq!(pool.count_matching(|value| value % 2 == 0), 1);
+    /// assert!(pool.any(|value| *value == 5));
+    /// assert!(!pool.all(|value| value % 2 == 0));
+    /// assert_eq!(pool.fold_values(0, |sum, value| sum + value), 9);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn count_matching(&self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.store
+            .iter()
+            .flatten()
+            .filter(|value| predicate(value))
+            .count()
+    }
+
+    /// Returns true if `predicate` returns true for any stored item. Stops at the first match.
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn any(&self, predicate: impl FnMut(&T) -> bool) -> bool {
+        self.store.iter().flatten().any(predicate)
+    }
+
+    /// Returns true if `predicate` returns true for all stored items, or if nothing is stored. Stops at the first mismatch.
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn all(&self, predicate: impl FnMut(&T) -> bool) -> bool {
+        self.store.iter().flatten().all(predicate)
+    }
+
+    /// Folds all stored items in slot order into one value, see [`Iterator::fold`].
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn fold_values<B>(&self, init: B, f: impl FnMut(B, &T) -> B) -> B {
+        self.store.iter().flatten().fold(init, f)
+    }
+
+    /// Calls `f` for every stored item in slot order, until it returns [`ControlFlow::Break`]. Returns the break value,
+    /// or [`None`] if `f` never broke.
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// pool.push(4);
+    /// let odd = pool.push(7);
+    /// pool.push(9);
+    ///
+    /// let first_odd = pool.for_each_until(|reference, value| {
+    ///     if value % 2 == 1 { ControlFlow::Break(reference) } else { ControlFlow::Continue(()) }
+    /// });
+    /// assert_eq!(first_odd, Some(odd));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn for_each_until<B>(
+        &self,
+        mut f: impl FnMut(ValueRef<T>, &T) -> ControlFlow<B>,
+    ) -> Option<B> {
+        match self
+            .occupied()
+            .try_for_each(|(reference, value)| f(reference, value))
+        {
+            ControlFlow::Break(value) => Some(value),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    /// Copies all stored items into a tightly packed [`Vec<T>`], in slot order, e.g. to upload them as one buffer.
+    /// The second [`Vec`] maps every dense position back to the ref of the item.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<f32> = ValuePool::new();
+    /// let a = pool.push(1.0);
+    /// let b = pool.push(2.0);
+    /// let c = pool.push(3.0);
+    /// pool.take(b);
+    ///
+    /// let (dense, refs) = pool.to_dense_vec();
+    /// assert_eq!(dense, vec![1.0, 3.0]);
+    /// assert_eq!(refs, vec![a, c]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)`
+    pub fn to_dense_vec(&self) -> (Vec<T>, Vec<ValueRef<T>>)
+    where
+        T: Clone,
+    {
+        self.occupied()
+            .map(|(reference, value)| (value.clone(), reference))
+            .unzip()
+    }
+
+    /// Same as [`ValuePool::to_dense_vec`], but moves the items instead of cloning them.
+    ///
+    /// # Complexity
+    /// `O(n)`
+    pub fn into_dense_vec(self) -> (Vec<T>, Vec<ValueRef<T>>) {
+        self.store
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((value?, ValueRef::new(index))))
+            .unzip()
+    }
+
+    /// Clears this [`ValuePool<T>`]. With the `slot-stats` feature, the [reuse counts](ValuePool::slot_reuse_count) are kept.
     /// ```
     /// use value_pool::ValuePool;
     ///
@@ -671,13 +1617,307 @@ impl<T> ValuePool<T> {
     #[inline]
     pub fn clear(&mut self) {
         self.open_indices.clear();
+        self.pinned.clear();
         self.store.clear();
     }
+
+    /// Keeps only the items for which `f` returns true, in slot order. `f` gets the position and a mut borrow of every item,
+    /// so it can update the item or external indexes of the items that get dropped in the same pass.
+    /// Dropped items leave their position empty, like [`ValuePool::take`].
+    /// Pinned items are always kept and are not passed to `f`.
+    /// ```
+    /// use std::collections::HashMap;
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let mut by_value = HashMap::new();
+    /// for value in [1, 2, 3, 4] {
+    ///     by_value.insert(value, pool.push(value));
+    /// }
+    ///
+    /// pool.retain_with_refs(|reference, value| {
+    ///     if *value % 2 == 0 {
+    ///         by_value.remove(value);
+    ///         return false;
+    ///     }
+    ///     true
+    /// });
+    /// assert_eq!(pool.element_count(), 2);
+    /// assert_eq!(pool.get(by_value[&3]), Some(&3));
+    /// assert!(!by_value.contains_key(&4));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn retain_with_refs(&mut self, mut f: impl FnMut(ValueRef<T>, &mut T) -> bool) {
+        let mut pinned = self.pinned.iter().peekable();
+        for (index, slot) in self.store.iter_mut().enumerate() {
+            if pinned.next_if(|pin| pin.get() == index).is_some() {
+                continue;
+            }
+            let Some(value) = slot else {
+                continue;
+            };
+            let reference = ValueRef::new(index);
+            if !f(reference, value) {
+                *slot = None;
+                self.open_indices.push(reference.index);
+            }
+        }
+    }
+
+    /// Drops all items for which `predicate` returns false and moves the remaining ones to the lowest positions, keeping
+    /// their order, in a single pass. Pinned items are neither passed to `predicate` nor moved; empty positions in front
+    /// of them stay empty. Returns a remap table: entry `i` is the new ref of the item that was at position `i`,
+    /// [`None`] if it got dropped or the position was empty.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let refs: Vec<_> = [1, 2, 3, 4].into_iter().map(|value| pool.push(value)).collect();
+    ///
+    /// let remap = pool.compact_retain(|value| value % 2 == 0);
+    /// assert_eq!(remap, vec![None, Some(refs[0]), None, Some(refs[1])]);
+    /// assert_eq!(pool.get(refs[1]), Some(&4));
+    /// assert_eq!(pool.waiting_positions(), 0);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn compact_retain(
+        &mut self,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> Vec<Option<ValueRef<T>>> {
+        /// Rebuilds `open_indices` from the store if `predicate` panics, so no empty position gets lost.
+        struct RebuildOnPanic<'a, T>(&'a mut ValuePool<T>);
+        impl<T> Drop for RebuildOnPanic<'_, T> {
+            fn drop(&mut self) {
+                if std::thread::panicking() {
+                    let pool = &mut *self.0;
+                    pool.open_indices = pool
+                        .store
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, slot)| slot.is_none())
+                        .map(|(index, _)| ValueRef::<T>::new(index).index)
+                        .collect();
+                }
+            }
+        }
+
+        let guard = RebuildOnPanic(self);
+        let pool = &mut *guard.0;
+        let mut remap = Vec::with_capacity(pool.store.len());
+        let mut open_indices = Vec::new();
+        let mut next_pin = 0;
+        let mut write = 0;
+        for read in 0..pool.store.len() {
+            if pool
+                .pinned
+                .get(next_pin)
+                .is_some_and(|pin| pin.get() == read)
+            {
+                next_pin += 1;
+                open_indices.extend((write..read).map(|gap| ValueRef::<T>::new(gap).index));
+                write = read + 1;
+                remap.push(Some(ValueRef::new(read)));
+                continue;
+            }
+            if !pool.store[read].as_ref().is_some_and(&mut predicate) {
+                pool.store[read] = None;
+                remap.push(None);
+                continue;
+            }
+            // everything in `write..read` got dropped or moved already
+            if write != read {
+                pool.store.swap(write, read);
+                #[cfg(feature = "slot-stats")]
+                pool.count_reuse(write);
+            }
+            remap.push(Some(ValueRef::new(write)));
+            write += 1;
+        }
+        pool.store.truncate(write);
+        pool.open_indices = open_indices;
+        remap
+    }
+
+    /// Calls `f` on the items at all `refs` and returns how many got updated.
+    /// The refs are sorted by position first, so memory is touched in order, which is faster than calling
+    /// [`ValuePool::get_mut`] in random order on large pools. Every item is updated once, even if its ref is given multiple times;
+    /// empty positions are skipped.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let a = pool.push(1);
+    /// let b = pool.push(2);
+    /// let c = pool.push(3);
+    /// pool.take(b);
+    ///
+    /// assert_eq!(pool.update_many([c, b, a, c], |value| *value *= 10), 2);
+    /// assert_eq!((pool.get(a), pool.get(c)), (Some(&10), Some(&30)));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(k log k)` where `k` is the number of refs
+    pub fn update_many(
+        &mut self,
+        refs: impl IntoIterator<Item = impl Into<ValueRef<T>>>,
+        mut f: impl FnMut(&mut T),
+    ) -> usize {
+        let mut indices: Vec<usize> = refs
+            .into_iter()
+            .map(|reference| reference.into().index.get())
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        let mut updated = 0;
+        for index in indices {
+            if let Some(Some(value)) = self.store.get_mut(index) {
+                f(value);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Moves the item at `reference` to the lowest empty position, if that one is lower, and returns the new position.
+    /// If the item stays, `reference` is returned. This allows to defragment a pool step by step instead of all at once.
+    ///
+    /// # Note
+    /// All other references equal to `reference` now point to an empty position (or a future item), just like after [`ValuePool::swap`].
+    /// Pinned items are never moved.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let a = pool.push("a");
+    /// pool.push("b");
+    /// let c = pool.push("c");
+    /// pool.take(a);
+    ///
+    /// let moved = pool.relocate(c);
+    /// assert_eq!(moved, a);
+    /// assert_eq!(pool.get(moved), Some(&"c"));
+    /// assert_eq!(pool.get(c), None);
+    /// assert_eq!(pool.relocate(moved), moved); // there is no lower empty position
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(w)` where `w` is the number of [waiting positions](ValuePool::waiting_positions)
+    pub fn relocate(&mut self, reference: impl Into<ValueRef<T>>) -> ValueRef<T> {
+        let reference: ValueRef<T> = reference.into();
+        if !self.has_item(reference) || self.is_pinned(reference) {
+            return reference;
+        }
+        let Some((position, lowest)) = self
+            .open_indices
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|(_, index)| *index)
+        else {
+            return reference;
+        };
+        if lowest >= reference.index {
+            return reference;
+        }
+        self.open_indices.swap_remove(position);
+        #[cfg(feature = "slot-stats")]
+        self.count_reuse(lowest.get());
+        self.store[lowest.get()] = self.store[reference.index.get()].take();
+        if reference.index.get() + 1 == self.store.len() {
+            self.store.pop();
+        } else {
+            self.open_indices.push(reference.index);
+        }
+        ValueRef::new_nonmax(lowest)
+    }
+
+    #[cfg(feature = "slot-stats")]
+    #[inline]
+    fn count_reuse(&mut self, index: usize) {
+        if index >= self.reuse_counts.len() {
+            self.reuse_counts.resize(index + 1, 0);
+        }
+        self.reuse_counts[index] += 1;
+    }
+
+    /// Counts the appended position `index` as reused if it held an item before, e.g. before [`ValuePool::clear`]
+    /// or a removal that shrank the store. `reuse_counts` is as long as the store ever was.
+    #[cfg(feature = "slot-stats")]
+    #[inline]
+    fn count_append(&mut self, index: usize) {
+        match self.reuse_counts.get_mut(index) {
+            Some(count) => *count += 1,
+            None => self.reuse_counts.resize(index + 1, 0),
+        }
+    }
+
+    /// Returns how often [`ValuePool::push`] reused the position of `reference` after its item got removed.
+    /// The counts never go down: they are kept by [`ValuePool::clear`], and pushes of a rolled back
+    /// [`Transaction`](crate::transaction::Transaction) stay counted.
+    /// Only available with the `slot-stats` feature.
+    #[cfg(feature = "slot-stats")]
+    #[inline]
+    pub fn slot_reuse_count(&self, reference: impl Into<ValueRef<T>>) -> u64 {
+        let reference: ValueRef<T> = reference.into();
+        self.reuse_counts
+            .get(reference.index.get())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns up to `n` positions that got reused most often, together with their reuse count, most reused first.
+    /// Positions that were never reused are left out. Slots that churn a lot are the likeliest to cause ABA-style bugs,
+    /// where an old [`ValueRef<T>`] silently points to a newer item.
+    /// Only available with the `slot-stats` feature.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let busy = pool.push(1);
+    /// pool.push(2);
+    /// for value in 0..3 {
+    ///     pool.take(busy);
+    ///     pool.push(value);
+    /// }
+    /// assert_eq!(pool.hottest_slots(5), vec![(busy, 3)]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(m log m)` where `m` is the number of reused positions
+    #[cfg(feature = "slot-stats")]
+    pub fn hottest_slots(&self, n: usize) -> Vec<(ValueRef<T>, u64)> {
+        let mut slots: Vec<(ValueRef<T>, u64)> = self
+            .reuse_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (ValueRef::new(index), *count))
+            .collect();
+        slots.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        slots.truncate(n);
+        slots
+    }
+
+    /// Iterates over all stored items in slot order, skipping empty positions.
+    #[inline]
+    pub(crate) fn occupied(&self) -> impl Iterator<Item = (ValueRef<T>, &T)> {
+        self.store
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((ValueRef::new(index), value.as_ref()?)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{UntypedValueRef, ValuePool, ValueRef};
+    use crate::{
+        Error, GrowthStrategy, MaxIndexError, SlotError, UntypedValueRef, ValuePool, ValueRef,
+    };
+    use std::ops::ControlFlow;
 
     fn get_store() -> ValuePool<u32> {
         let mut store: ValuePool<u32> = ValuePool::with_capacity(10);
@@ -717,6 +1957,409 @@ mod tests {
         assert_eq!(store.next_push_ref(), ValueRef::new(10));
     }
 
+    #[test]
+    fn test_std_names() {
+        let mut store = get_store();
+        let inserted = store.insert(7);
+        assert_eq!(store.len(), 12);
+        // the last item shrinks the store, others leave a waiting position
+        assert_eq!(store.remove(inserted), Some(7));
+        assert_eq!(store.remove(ValueRef::new(2)), Some(123));
+        assert_eq!(store.remove(ValueRef::new(2)), None);
+        assert_eq!((store.len(), store.waiting_positions()), (10, 1));
+    }
+
+    #[test]
+    fn test_retain_with_refs() {
+        let mut store = get_store();
+        store.pin_slot(ValueRef::new(1));
+        let mut dropped = Vec::new();
+        store.retain_with_refs(|reference, value| {
+            *value += 1;
+            if *value > 6 {
+                return true;
+            }
+            dropped.push(reference.index.get());
+            false
+        });
+        // position 1 (3) is pinned and neither updated nor dropped
+        assert_eq!(dropped, vec![3, 4, 5, 7, 8]);
+        assert_eq!(store.get(ValueRef::new(1)), Some(&3));
+        assert_eq!(store.get(ValueRef::new(0)), Some(&13));
+        assert_eq!((store.element_count(), store.waiting_positions()), (6, 5));
+    }
+
+    #[test]
+    fn test_pinned_items_stay_in_place() {
+        let mut store = get_store();
+        store.pin_slot(ValueRef::new(1));
+        assert_eq!(store.replace(ValueRef::new(1), None), Err(None));
+        assert_eq!(store.swap(ValueRef::new(1), ValueRef::new(2)), None);
+        assert_eq!(store.swap(ValueRef::new(2), ValueRef::new(1)), None);
+        assert_eq!(store.get(ValueRef::new(1)), Some(&3));
+        assert!(store.is_pinned(ValueRef::new(1)));
+
+        // replacing the item keeps the position filled
+        assert_eq!(store.replace(ValueRef::new(1), Some(4)), Ok(Some(3)));
+        assert!(store.swap(ValueRef::new(0), ValueRef::new(2)).is_some());
+        store.unpin_slot(ValueRef::new(1));
+        assert!(store.swap(ValueRef::new(1), ValueRef::new(2)).is_some());
+        assert_eq!(store.get(ValueRef::new(2)), Some(&4));
+    }
+
+    #[cfg(feature = "slot-stats")]
+    #[test]
+    fn test_hottest_slots() {
+        let mut store = get_store();
+        for index in [2, 5, 2] {
+            store.take(ValueRef::new(index));
+            store.push(0);
+        }
+        assert_eq!(store.hottest_slots(1), vec![(ValueRef::new(2), 2)]);
+        assert_eq!(store.hottest_slots(5).len(), 2);
+        assert_eq!(store.slot_reuse_count(ValueRef::new(5)), 1);
+
+        // the counts survive `clear`, and pushing to a formerly used position counts
+        store.clear();
+        assert_eq!(store.hottest_slots(1), vec![(ValueRef::new(2), 2)]);
+        store.push(0);
+        assert_eq!(store.slot_reuse_count(ValueRef::new(0)), 1);
+        store.remove(ValueRef::new(0));
+        store.push(0);
+        assert_eq!(store.slot_reuse_count(ValueRef::new(0)), 2);
+    }
+
+    #[test]
+    fn test_find_fast() {
+        let mut store = get_store();
+        store.take(ValueRef::new(1));
+        for value in [12, 3, 123, 5, 74, 52, 99] {
+            assert_eq!(store.find_fast(value), store.find(&value));
+        }
+        assert_eq!(store.find_fast(3), Some(ValueRef::new(7)));
+    }
+
+    #[test]
+    fn test_aggregates_skip_empty_positions() {
+        let mut store = get_store();
+        store.take(ValueRef::new(1));
+        store.take(ValueRef::new(7));
+        assert_eq!(store.count_matching(|value| *value == 3), 0);
+        assert_eq!(store.count_matching(|value| *value == 5), 2);
+        assert!(store.all(|value| *value != 3));
+        assert!(!store.any(|value| *value == 3));
+        assert_eq!(store.fold_values(0, |max, value| max.max(*value)), 123);
+        assert!(ValuePool::<u32>::new().all(|_| false));
+
+        let mut visited = 0;
+        let found = store.for_each_until(|reference, value| {
+            visited += 1;
+            if *value == 5 {
+                ControlFlow::Break(reference)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!((found, visited), (Some(ValueRef::new(3)), 3));
+        assert_eq!(
+            store.for_each_until(|_, _| ControlFlow::<()>::Continue(())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_error_conversions() {
+        let mut store = get_store();
+        store.take(ValueRef::new(2));
+        store.pin_slot(ValueRef::new(3));
+        assert_eq!(store.try_get(ValueRef::new(2)), Err(Error::Vacant));
+        assert_eq!(
+            store.try_get_mut(ValueRef::new(11)),
+            Err(Error::OutOfBounds)
+        );
+        *store.try_get_mut(ValueRef::new(0)).unwrap() += 1;
+        assert_eq!(store.try_get(ValueRef::new(0)), Ok(&13));
+
+        let take = |store: &mut ValuePool<u32>, index| -> Result<u32, Error> {
+            Ok(store.try_take(ValueRef::try_from(index)?)?)
+        };
+        assert_eq!(take(&mut store, 3), Err(Error::Pinned));
+        assert_eq!(take(&mut store, usize::MAX), Err(Error::MaxIndex));
+        assert_eq!(
+            store.try_reserve(usize::MAX).map_err(Error::from),
+            Err(Error::CapacityExceeded)
+        );
+        assert_eq!(Error::Vacant.to_string(), SlotError::Empty.to_string());
+    }
+
+    #[test]
+    fn test_dense_vec() {
+        let mut store = get_store();
+        for index in [0, 4, 10] {
+            store.take(ValueRef::new(index));
+        }
+        let (dense, refs) = store.to_dense_vec();
+        assert_eq!(dense, vec![3, 123, 5, 5, 8, 3, 0, 74]);
+        for (value, reference) in dense.iter().zip(&refs) {
+            assert_eq!(store.get(*reference), Some(value));
+        }
+        assert_eq!(store.into_dense_vec(), (dense, refs));
+    }
+
+    #[test]
+    fn test_compact_retain() {
+        let mut store = get_store();
+        store.take(ValueRef::new(1));
+        store.pin_slot(ValueRef::new(4));
+        let remap = store.compact_retain(|value| *value > 5);
+        let moved: Vec<Option<usize>> = remap
+            .iter()
+            .map(|reference| reference.map(|reference| reference.index.get()))
+            .collect();
+        #[rustfmt::skip]
+        assert_eq!(moved, vec![Some(0), None, Some(1), None, Some(4), None, Some(5), None, None, Some(6), Some(7)]);
+        assert_eq!(store.store.len(), 8);
+        let mut open: Vec<usize> = store.open_indices.iter().map(|index| index.get()).collect();
+        open.sort_unstable();
+        assert_eq!(open, vec![2, 3]);
+        assert_eq!(store.get(ValueRef::new(5)), Some(&8));
+        assert_eq!(store.get(ValueRef::new(7)), Some(&52));
+        assert!(store.is_pinned(ValueRef::new(4)));
+    }
+
+    #[test]
+    fn test_growth_hook() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static LAST_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+        let mut store = get_store();
+        store.set_growth_strategy(GrowthStrategy::Fixed(4));
+        store.set_growth_hook(Some(|old, new| {
+            assert!(new > old);
+            LAST_CAPACITY.store(new, Ordering::Relaxed);
+        }));
+        while store.store.len() < store.capacity() {
+            store.push(0);
+        }
+        assert_eq!(LAST_CAPACITY.load(Ordering::Relaxed), 0);
+        let full = store.capacity();
+        store.push(0);
+        assert_eq!(LAST_CAPACITY.load(Ordering::Relaxed), full + 4);
+        store.reserve(100);
+        assert_eq!(LAST_CAPACITY.load(Ordering::Relaxed), store.capacity());
+
+        store.set_growth_hook(None);
+        store.reserve(1000);
+        assert!(store.growth_hook().is_none());
+        assert_ne!(LAST_CAPACITY.load(Ordering::Relaxed), store.capacity());
+    }
+
+    #[test]
+    fn test_compact_retain_panic_keeps_positions() {
+        let mut store = get_store();
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            store.compact_retain(|value| {
+                calls += 1;
+                assert!(calls < 6, "predicate failed");
+                *value > 10
+            })
+        }));
+        assert!(result.is_err());
+        // 12 and 123 got moved to the front, 3, 5 and 1 got dropped
+        let remaining = store.store.iter().flatten().count();
+        assert_eq!(store.element_count(), remaining);
+        assert_eq!(store.waiting_positions(), 3);
+        assert_eq!(store.push(1), ValueRef::new(4));
+    }
+
+    #[test]
+    fn test_usize_conversions() {
+        assert_eq!(UntypedValueRef::try_from(3), Ok(UntypedValueRef::new(3)));
+        assert_eq!(UntypedValueRef::try_from(usize::MAX), Err(MaxIndexError));
+        assert_eq!(ValueRef::<u8>::try_from(usize::MAX), Err(MaxIndexError));
+        let typed: ValueRef<u8> = ValueRef::try_from(7).unwrap();
+        assert_eq!(usize::from(typed), 7);
+
+        let mut range = UntypedValueRef::range(UntypedValueRef::new(1)..typed.into());
+        assert_eq!(range.len(), 6);
+        assert_eq!(range.next_back(), Some(UntypedValueRef::new(6)));
+        assert_eq!(
+            UntypedValueRef::range(typed.into()..UntypedValueRef::new(2)).len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_push_within_capacity() {
+        let mut store = get_store();
+        store.take(ValueRef::new(4));
+        let free = store.store.capacity() - store.store.len();
+        assert_eq!(store.push_within_capacity(7), Ok(ValueRef::new(4)));
+        for _ in 0..free {
+            assert!(store.push_within_capacity(7).is_ok());
+        }
+        assert_eq!(store.push_within_capacity(7), Err(7));
+        assert!(store.try_reserve(usize::MAX).is_err());
+        assert!(store.try_reserve(3).is_ok());
+        assert!(store.push_within_capacity(7).is_ok());
+    }
+
+    #[test]
+    fn test_update_many() {
+        let mut store = get_store();
+        let refs = [10, 0, 42, 10, 5].map(ValueRef::new);
+        let mut order = Vec::new();
+        assert_eq!(
+            store.update_many(refs, |value| {
+                order.push(*value);
+                *value += 1;
+            }),
+            3
+        );
+        // in slot order, the duplicate and out of bounds refs are skipped
+        assert_eq!(order, vec![12, 5, 52]);
+        assert_eq!(store.get(ValueRef::new(10)), Some(&53));
+    }
+
+    #[test]
+    fn test_relocate() {
+        let mut store = get_store();
+        store.take(ValueRef::new(4));
+        store.take(ValueRef::new(2));
+        store.pin_slot(ValueRef::new(9));
+        assert_eq!(store.relocate(ValueRef::new(9)), ValueRef::new(9));
+        assert_eq!(store.relocate(ValueRef::new(1)), ValueRef::new(1));
+
+        // the last item shrinks the store
+        assert_eq!(store.relocate(ValueRef::new(10)), ValueRef::new(2));
+        assert_eq!(store.get(ValueRef::new(2)), Some(&52));
+        assert_eq!((store.len(), store.waiting_positions()), (9, 1));
+        assert_eq!(store.relocate(ValueRef::new(8)), ValueRef::new(4));
+        assert_eq!(store.next_push_ref(), ValueRef::new(8));
+        assert_eq!(store.relocate(ValueRef::new(8)), ValueRef::new(8));
+    }
+
+    #[test]
+    fn test_multi_get() {
+        let store = get_store();
+        let (a, b, missing) = (ValueRef::new(0), ValueRef::new(10), ValueRef::new(11));
+        assert_eq!(store.get3(a, b, missing), (Some(&12), Some(&52), None));
+        assert_eq!(
+            store.get4(b, b, a, UntypedValueRef::new(2)),
+            (Some(&52), Some(&52), Some(&12), Some(&123))
+        );
+    }
+
+    #[test]
+    fn test_ref_bits() {
+        let reference: ValueRef<u32> = ValueRef::new(u32::MAX as usize - 1);
+        assert_eq!(reference.try_to_bits(), Some(u32::MAX as u64 - 1));
+        assert_eq!(ValueRef::from_bits(u32::MAX as u64 - 1), Some(reference));
+        assert_eq!(ValueRef::<u32>::from_bits(1 << 32), None);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            ValueRef::<u32>::new(u32::MAX as usize + 1).try_to_bits(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pinned_slots() {
+        let mut store = get_store();
+        let first = ValueRef::new(0);
+        let last = ValueRef::new(10);
+        assert!(store.pin_slot(first));
+        assert!(store.pin_slot(last));
+        assert!(!store.pin_slot(ValueRef::new(11)));
+
+        assert_eq!(store.remove(first), None);
+        assert_eq!(store.try_remove(first), Err(crate::SlotError::Pinned));
+        assert_eq!(store.try_remove(ValueRef::new(1)), Ok(3));
+        assert_eq!(
+            store.try_remove(ValueRef::new(1)),
+            Err(crate::SlotError::Empty)
+        );
+        assert_eq!(unsafe { store.remove_full(first) }, None);
+        assert_eq!(store.get(first), Some(&12));
+
+        // the pin of `last` moves to the position of the removed item
+        assert_eq!(unsafe { store.remove_full(ValueRef::new(3)) }, Some(5));
+        assert_eq!(store.get(ValueRef::new(3)), Some(&52));
+        assert!(store.is_pinned(ValueRef::new(3)));
+        assert!(!store.is_pinned(last));
+
+        assert!(store.unpin_slot(first));
+        assert!(!store.unpin_slot(first));
+        assert_eq!(store.try_take(first), Ok(12));
+        assert_eq!(store.try_take(first), Err(crate::SlotError::Empty));
+    }
+
+    #[test]
+    #[cfg(feature = "paranoid")]
+    fn test_paranoid_unchecked_access() {
+        let mut store = get_store();
+        let out_of_bounds = ValueRef::new(11);
+        unsafe {
+            assert_eq!(store.get_unchecked(out_of_bounds), None);
+            assert_eq!(store.get_unchecked_mut(out_of_bounds), None);
+            assert_eq!(store.take_unchecked(out_of_bounds), None);
+            assert_eq!(store.take_unchecked(ValueRef::new(10)), Some(52));
+        }
+
+        thread_local! {
+            static CALLS: std::cell::RefCell<Vec<(&'static str, usize, usize)>> = const { std::cell::RefCell::new(Vec::new()) };
+        }
+        store.set_out_of_bounds_hook(Some(|method, index, len| {
+            CALLS.with_borrow_mut(|calls| calls.push((method, index, len)))
+        }));
+        unsafe {
+            assert_eq!(store.get_unchecked(out_of_bounds), None);
+            assert_eq!(store.get_unchecked(ValueRef::new(0)), Some(&12));
+        }
+        assert_eq!(CALLS.take(), vec![("get_unchecked", 11, 11)]);
+        assert!(store.clone().out_of_bounds_hook().is_some());
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, not(feature = "paranoid")))]
+    #[should_panic(expected = "out of bounds")]
+    fn test_unchecked_access_asserts_in_debug() {
+        let store = get_store();
+        unsafe {
+            store.get_unchecked(ValueRef::new(11));
+        }
+    }
+
+    #[test]
+    fn test_auto_traits() {
+        use crate::{
+            gap_list::GapList, keyed_pool::KeyedPool, read_mostly_pool::ReadMostlyPool,
+            ref_bi_map::RefBiMap, smart_value_pool::SmartValuePool, sync_value_ref::SyncValueRef,
+            thread_local_pool::ThreadLocalPool, transaction::Transaction,
+        };
+        use std::{cell::Cell, rc::Rc};
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        fn assert_send<T: Send>() {}
+
+        // handles never store a `T`
+        assert_send_sync::<ValueRef<Rc<u32>>>();
+        assert_send_sync::<UntypedValueRef>();
+        assert_send_sync::<RefBiMap<Rc<u32>, Cell<u32>>>();
+        assert_send_sync::<ThreadLocalPool<Rc<u32>>>();
+        // containers follow `T`
+        assert_send_sync::<ValuePool<u32>>();
+        assert_send::<ValuePool<Cell<u32>>>();
+        assert_send_sync::<SmartValuePool<u32, Rc<u32>>>();
+        assert_send_sync::<KeyedPool<u32, u32>>();
+        assert_send_sync::<GapList<u32>>();
+        assert_send_sync::<Transaction<'static, u32>>();
+        assert_send_sync::<SyncValueRef<u32>>();
+        assert_send_sync::<ReadMostlyPool<u32>>();
+        assert_send::<ReadMostlyPool<Cell<u32>>>();
+    }
+
     #[test]
     fn test_correct_sizes() {
         struct Dummy;
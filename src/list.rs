@@ -0,0 +1,11 @@
+//! This module groups the list types of this crate, which store their elements in [`ValuePool`](crate::ValuePool)s.
+//! Every module stays available at its old path as well, e.g. [`crate::multi_list`].
+//! ```
+//! use value_pool::list::multi_list::MultiList;
+//!
+//! let mut lists: MultiList<u32> = MultiList::new();
+//! let list = lists.create_list();
+//! let reference = lists.insert(1);
+//! assert!(lists.push_back(list, reference));
+//! ```
+pub use crate::{gap_list, multi_list};
@@ -0,0 +1,123 @@
+//! This module implements [`ValuePool::render_metrics`], which exports the state of a pool in the Prometheus text exposition format.
+use std::fmt::Write;
+
+use crate::ValuePool;
+
+impl<T> ValuePool<T> {
+    /// Renders gauges describing this [`ValuePool`] in the Prometheus text exposition format, e.g. for a `/metrics` endpoint.
+    /// Every metric name starts with `prefix`, which must be a valid Prometheus metric name like `mesh_pool`.
+    /// Render one pool per type with its own prefix and concatenate the outputs.
+    ///
+    /// The exported metrics are `<prefix>_elements`, `<prefix>_capacity`, `<prefix>_slots`, `<prefix>_waiting_positions`,
+    /// `<prefix>_pinned` and `<prefix>_fragmentation` (the ratio of waiting positions to slots).
+    /// With the `slot-stats` feature the counter `<prefix>_slot_reuses_total` is added.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let first = pool.push(1);
+    /// pool.push(2);
+    /// pool.take(first);
+    ///
+    /// let metrics = pool.render_metrics("numbers");
+    /// assert!(metrics.contains("# TYPE numbers_elements gauge\nnumbers_elements 1\n"));
+    /// assert!(metrics.contains("numbers_fragmentation 0.5\n"));
+    /// ```
+    ///
+    /// # Panic
+    /// Debug builds panic if `prefix` isn't a valid metric name.
+    ///
+    /// # Complexity
+    /// `O(1)`, `O(n)` with the `slot-stats` feature
+    pub fn render_metrics(&self, prefix: &str) -> String {
+        debug_assert!(
+            is_metric_name(prefix),
+            "`{prefix}` is not a valid Prometheus metric name"
+        );
+        let slots = self.store.len();
+        let fragmentation = if slots == 0 {
+            0.0
+        } else {
+            self.waiting_positions() as f64 / slots as f64
+        };
+
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: &dyn std::fmt::Display| {
+            // writing to a `String` can't fail
+            let _ = write!(
+                out,
+                "# HELP {prefix}_{name} {help}\n# TYPE {prefix}_{name} {kind}\n{prefix}_{name} {value}\n"
+            );
+        };
+        metric(
+            "elements",
+            "gauge",
+            "Number of stored elements.",
+            &self.element_count(),
+        );
+        metric(
+            "capacity",
+            "gauge",
+            "Number of slots that fit without reallocation.",
+            &self.capacity(),
+        );
+        metric(
+            "slots",
+            "gauge",
+            "Number of slots, stored or waiting.",
+            &slots,
+        );
+        metric(
+            "waiting_positions",
+            "gauge",
+            "Number of empty slots waiting to be reused.",
+            &self.waiting_positions(),
+        );
+        metric(
+            "pinned",
+            "gauge",
+            "Number of pinned slots.",
+            &self.pinned.len(),
+        );
+        metric(
+            "fragmentation",
+            "gauge",
+            "Ratio of waiting positions to slots.",
+            &fragmentation,
+        );
+        #[cfg(feature = "slot-stats")]
+        metric(
+            "slot_reuses_total",
+            "counter",
+            "Number of times a push reused a waiting position.",
+            &self.reuse_counts.iter().sum::<u64>(),
+        );
+        out
+    }
+}
+
+fn is_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_' || first == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValuePool;
+
+    #[test]
+    fn test_render_empty_pool() {
+        let pool: ValuePool<u32> = ValuePool::new();
+        let metrics = pool.render_metrics("empty");
+        assert!(metrics.starts_with(
+            "# HELP empty_elements Number of stored elements.\n# TYPE empty_elements gauge\nempty_elements 0\n"
+        ));
+        assert!(metrics.contains("\nempty_fragmentation 0\n"));
+        assert!(metrics
+            .lines()
+            .all(|line| line.starts_with('#') || line.starts_with("empty_")));
+    }
+}
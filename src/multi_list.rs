@@ -0,0 +1,1277 @@
+//! This module implements [`MultiList<T>`], which stores values that can be members of several doubly linked lists at once.
+use crate::{ValuePool, ValueRef};
+use std::{cmp::Ordering, ops::ControlFlow};
+
+/// Identifies one list of a [`MultiList<T>`]. Returned by [`MultiList::create_list`]; only use it with the [`MultiList`] that returned it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListId(usize);
+
+/// One half of a list returned by [`MultiList::split_mut`]: its values with their positions, from front to back.
+pub type SplitHalf<'a, T> = Vec<(ValueRef<T>, &'a mut T)>;
+
+#[derive(Debug)]
+struct Link<T> {
+    value: ValueRef<T>,
+    prev: ValueRef<Link<T>>,
+    next: ValueRef<Link<T>>,
+}
+
+#[derive(Debug)]
+struct ListHead<T> {
+    // lists are rings, the back is `head.prev`
+    head: Option<ValueRef<Link<T>>>,
+    len: usize,
+}
+
+/// A [`MultiList<T>`] stores values in a [`ValuePool<T>`] and links them into any number of doubly linked lists.
+/// One value can be a member of several lists at the same time, but of every list only once, e.g. a task that sits in
+/// both a timer queue and a ready queue. The links are stored in a second pool, so the values never move.
+/// ```
+/// use value_pool::multi_list::MultiList;
+///
+/// let mut scheduler: MultiList<&str> = MultiList::new();
+/// let ready = scheduler.create_list();
+/// let timers = scheduler.create_list();
+///
+/// let render = scheduler.insert("render");
+/// let net = scheduler.insert("net");
+/// scheduler.push_back(ready, render);
+/// scheduler.push_back(ready, net);
+/// scheduler.push_back(timers, net);
+///
+/// assert_eq!(scheduler.pop_front(ready), Some(render));
+/// assert_eq!(scheduler.iter(ready).map(|(_, task)| *task).collect::<Vec<_>>(), vec!["net"]);
+///
+/// // removing a value unlinks it from all of its lists
+/// assert_eq!(scheduler.remove(net), Some("net"));
+/// assert!(scheduler.is_list_empty(ready) && scheduler.is_list_empty(timers));
+/// ```
+#[derive(Debug)]
+pub struct MultiList<T> {
+    values: ValuePool<T>,
+    links: ValuePool<Link<T>>,
+    lists: Vec<ListHead<T>>,
+    // the links of every value, by index of the value
+    memberships: Vec<Vec<(ListId, ValueRef<Link<T>>)>>,
+}
+
+impl<T> Default for MultiList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MultiList<T> {
+    /// Creates a new, empty [`MultiList`] without lists.
+    #[inline]
+    pub const fn new() -> MultiList<T> {
+        MultiList {
+            values: (ValuePool::new()),
+            links: (ValuePool::new()),
+            lists: (Vec::new()),
+            memberships: (Vec::new()),
+        }
+    }
+
+    /// Creates a [`MultiList`] that stores the values of `values` and links them into one list, in the order given by `order`,
+    /// e.g. after sorting their refs externally. All refs into `values` stay valid.
+    /// Refs that point to no value or repeat are skipped, like [`MultiList::push_back`] does.
+    /// ```
+    /// use value_pool::{multi_list::MultiList, ValuePool};
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let mut order: Vec<_> = [3, 1, 2].into_iter().map(|value| pool.push(value)).collect();
+    /// order.sort_by_key(|reference| pool.get(*reference).copied());
+    ///
+    /// let (lists, list) = MultiList::from_order(pool, order);
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of `order`
+    pub fn from_order(
+        values: ValuePool<T>,
+        order: impl IntoIterator<Item = impl Into<ValueRef<T>>>,
+    ) -> (MultiList<T>, ListId) {
+        let mut lists = MultiList {
+            values,
+            ..MultiList::new()
+        };
+        let list = lists.create_list();
+        for reference in order {
+            lists.push_back(list, reference);
+        }
+        (lists, list)
+    }
+
+    /// Creates a new, empty list.
+    #[inline]
+    pub fn create_list(&mut self) -> ListId {
+        self.lists.push(ListHead { head: None, len: 0 });
+        ListId(self.lists.len() - 1)
+    }
+
+    /// Gives read-only access to the pool of all values.
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<T> {
+        &self.values
+    }
+
+    /// Drops all lists and returns the pool of all values, e.g. to analyse or compact it in bulk.
+    /// Every value stays at its position, so all refs stay valid. See [`MultiList::from_order`] for the way back.
+    #[inline]
+    pub fn into_pool(self) -> ValuePool<T> {
+        self.values
+    }
+
+    /// Stores `value` without adding it to any list.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn insert(&mut self, value: T) -> ValueRef<T> {
+        self.values.push(value)
+    }
+
+    /// Same as [`ValuePool::get`].
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.values.get(reference)
+    }
+
+    /// Same as [`ValuePool::get_mut`].
+    #[inline]
+    pub fn get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Option<&mut T> {
+        self.values.get_mut(reference)
+    }
+
+    /// Swaps the values stored at `first` and `second`, but not their memberships:
+    /// Every list keeps its order of positions and now sees the other value there.
+    /// Returns false if no value is stored at one of them, or one of them is [pinned](ValuePool::pin_slot) in the pool passed to [`MultiList::from_order`].
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<&str> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let (a, b) = (lists.insert("a"), lists.insert("b"));
+    /// lists.push_back(list, a);
+    /// lists.push_back(list, b);
+    ///
+    /// assert!(lists.swap_values(a, b));
+    /// assert_eq!(lists.get(a), Some(&"b"));
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec!["b", "a"]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn swap_values(
+        &mut self,
+        first: impl Into<ValueRef<T>>,
+        second: impl Into<ValueRef<T>>,
+    ) -> bool {
+        let (first, second): (ValueRef<T>, ValueRef<T>) = (first.into(), second.into());
+        if !self.values.has_item(first) || !self.values.has_item(second) {
+            return false;
+        }
+        self.values.swap(first, second).is_some()
+    }
+
+    /// Removes the value at `reference` from all of its lists and returns it.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value is a member of
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.values.take(reference)?;
+        if let Some(memberships) = self.memberships.get_mut(reference.index.get()) {
+            for (list, link) in std::mem::take(memberships) {
+                self.unlink_raw(list, link);
+            }
+        }
+        Some(value)
+    }
+
+    /// Keeps the members of `list` for which `f(reference, value)` returns true and [removes](MultiList::remove) all others,
+    /// from all of their lists. Returns the removed values with their positions, from front to back,
+    /// so indexes that map to these positions can be purged in the same pass.
+    /// ```
+    /// use std::collections::HashMap;
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<u32> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let mut by_name = HashMap::new();
+    /// for (name, value) in [("a", 1), ("b", 2), ("c", 3)] {
+    ///     let reference = lists.insert(value);
+    ///     lists.push_back(list, reference);
+    ///     by_name.insert(name, reference);
+    /// }
+    ///
+    /// let removed = lists.retain(list, |_, value| value % 2 == 1);
+    /// assert_eq!(removed, vec![(by_name["b"], 2)]);
+    /// by_name.retain(|_, reference| removed.iter().all(|(removed, _)| removed != reference));
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n + r * m)` where `n` is the length of `list`, `r` the number of removed values and `m` the number of lists they are members of
+    pub fn retain(
+        &mut self,
+        list: ListId,
+        mut f: impl FnMut(ValueRef<T>, &T) -> bool,
+    ) -> Vec<(ValueRef<T>, T)> {
+        let dropped: Vec<ValueRef<T>> = self
+            .iter(list)
+            .filter(|(reference, value)| !f(*reference, value))
+            .map(|(reference, _)| reference)
+            .collect();
+        dropped
+            .into_iter()
+            .filter_map(|reference| Some((reference, self.remove(reference)?)))
+            .collect()
+    }
+
+    /// Returns the number of values in `list`.
+    #[inline]
+    pub fn list_len(&self, list: ListId) -> usize {
+        self.lists.get(list.0).map_or(0, |head| head.len)
+    }
+
+    /// Returns true if `list` contains no values.
+    #[inline]
+    pub fn is_list_empty(&self, list: ListId) -> bool {
+        self.list_len(list) == 0
+    }
+
+    #[inline]
+    fn membership(&self, list: ListId, reference: ValueRef<T>) -> Option<ValueRef<Link<T>>> {
+        self.memberships
+            .get(reference.index.get())?
+            .iter()
+            .find(|(member_of, _)| *member_of == list)
+            .map(|(_, link)| *link)
+    }
+
+    /// Returns true if the value at `reference` is a member of `list`.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value is a member of
+    #[inline]
+    pub fn contains(&self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        self.membership(list, reference.into()).is_some()
+    }
+
+    /// Links the value at `reference` into `list` as its back and returns the new link.
+    fn link(&mut self, list: ListId, reference: ValueRef<T>) -> Option<ValueRef<Link<T>>> {
+        if !self.values.has_item(reference)
+            || list.0 >= self.lists.len()
+            || self.contains(list, reference)
+        {
+            return None;
+        }
+        // the ring gets closed below, once the position of the link is known
+        let link = self.links.push(Link {
+            value: reference,
+            prev: (ValueRef::new(0)),
+            next: (ValueRef::new(0)),
+        });
+        let (prev, next) = match self.lists[list.0].head {
+            None => (link, link),
+            Some(head) => (self.link_at(head).prev, head),
+        };
+        let new = self.link_at_mut(link);
+        (new.prev, new.next) = (prev, next);
+        self.link_at_mut(prev).next = link;
+        self.link_at_mut(next).prev = link;
+
+        let index = reference.index.get();
+        if index >= self.memberships.len() {
+            self.memberships.resize_with(index + 1, Vec::new);
+        }
+        self.memberships[index].push((list, link));
+        let head = &mut self.lists[list.0];
+        head.len += 1;
+        head.head.get_or_insert(link);
+        Some(link)
+    }
+
+    /// Appends the value at `reference` to `list`.
+    /// Returns false if no value is stored at `reference` or it's already a member of `list`.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value is a member of
+    #[inline]
+    pub fn push_back(&mut self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        self.link(list, reference.into()).is_some()
+    }
+
+    /// Prepends the value at `reference` to `list`.
+    /// Returns false if no value is stored at `reference` or it's already a member of `list`.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value is a member of
+    #[inline]
+    pub fn push_front(&mut self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        let Some(link) = self.link(list, reference.into()) else {
+            return false;
+        };
+        self.lists[list.0].head = Some(link);
+        true
+    }
+
+    /// Removes the value at `reference` from `list`; the value stays stored. Returns false if it wasn't a member.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value is a member of
+    pub fn unlink(&mut self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        let Some(memberships) = self.memberships.get_mut(reference.index.get()) else {
+            return false;
+        };
+        let Some(position) = memberships
+            .iter()
+            .position(|(member_of, _)| *member_of == list)
+        else {
+            return false;
+        };
+        let (_, link) = memberships.swap_remove(position);
+        self.unlink_raw(list, link);
+        true
+    }
+
+    /// Removes `link` from the ring of `list`, without touching the memberships.
+    fn unlink_raw(&mut self, list: ListId, link: ValueRef<Link<T>>) {
+        let Link { prev, next, .. } = self.links.take(link).expect("Links to be stored");
+        let head = &mut self.lists[list.0];
+        head.len -= 1;
+        if head.len == 0 {
+            head.head = None;
+            return;
+        }
+        if head.head == Some(link) {
+            head.head = Some(next);
+        }
+        self.link_at_mut(prev).next = next;
+        self.link_at_mut(next).prev = prev;
+    }
+
+    #[inline]
+    fn link_at(&self, link: ValueRef<Link<T>>) -> &Link<T> {
+        self.links.get(link).expect("Links to be stored")
+    }
+
+    #[inline]
+    fn link_at_mut(&mut self, link: ValueRef<Link<T>>) -> &mut Link<T> {
+        self.links.get_mut(link).expect("Links to be stored")
+    }
+
+    /// Returns the first value of `list`.
+    #[inline]
+    pub fn front(&self, list: ListId) -> Option<ValueRef<T>> {
+        let head = self.lists.get(list.0)?.head?;
+        Some(self.link_at(head).value)
+    }
+
+    /// Returns the last value of `list`.
+    #[inline]
+    pub fn back(&self, list: ListId) -> Option<ValueRef<T>> {
+        let head = self.lists.get(list.0)?.head?;
+        Some(self.link_at(self.link_at(head).prev).value)
+    }
+
+    /// Removes the first value of `list` from it and returns its position; the value stays stored.
+    #[inline]
+    pub fn pop_front(&mut self, list: ListId) -> Option<ValueRef<T>> {
+        let front = self.front(list)?;
+        self.unlink(list, front);
+        Some(front)
+    }
+
+    /// Removes the last value of `list` from it and returns its position; the value stays stored.
+    #[inline]
+    pub fn pop_back(&mut self, list: ListId) -> Option<ValueRef<T>> {
+        let back = self.back(list)?;
+        self.unlink(list, back);
+        Some(back)
+    }
+
+    /// Swaps the values of the neighbouring links `first` and `second` of `list`; the links stay in place.
+    fn swap_link_values(
+        &mut self,
+        list: ListId,
+        first: ValueRef<Link<T>>,
+        second: ValueRef<Link<T>>,
+    ) {
+        let first_value = self.link_at(first).value;
+        let second_value = self.link_at(second).value;
+        self.link_at_mut(first).value = second_value;
+        self.link_at_mut(second).value = first_value;
+        self.set_membership(list, first_value, second);
+        self.set_membership(list, second_value, first);
+    }
+
+    /// Points the membership of `value` in `list` to `link`.
+    #[inline]
+    fn set_membership(&mut self, list: ListId, value: ValueRef<T>, link: ValueRef<Link<T>>) {
+        if let Some(membership) = self.memberships[value.index.get()]
+            .iter_mut()
+            .find(|(member_of, _)| *member_of == list)
+        {
+            membership.1 = link;
+        }
+    }
+
+    /// Relinks `list` into the order given by `order`, which has to contain every member of `list` exactly once,
+    /// e.g. after sorting the result of [`MultiList::iter`] externally. The values don't move.
+    /// Returns false and leaves `list` unchanged if `order` isn't a permutation of the members.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<u32> = MultiList::new();
+    /// let list = lists.create_list();
+    /// for value in [3, 1, 2] {
+    ///     let reference = lists.insert(value);
+    ///     lists.push_back(list, reference);
+    /// }
+    ///
+    /// let mut order: Vec<_> = lists.iter(list).collect();
+    /// order.sort_by_key(|(_, value)| **value);
+    /// let order: Vec<_> = order.into_iter().map(|(reference, _)| reference).collect();
+    /// assert!(lists.reorder_to(list, order));
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n log n + n * m)` where `n` is the length of `list` and `m` the number of lists a value is a member of
+    pub fn reorder_to(
+        &mut self,
+        list: ListId,
+        order: impl IntoIterator<Item = impl Into<ValueRef<T>>>,
+    ) -> bool {
+        let order: Vec<ValueRef<T>> = order.into_iter().map(Into::into).collect();
+        if order.len() != self.list_len(list) {
+            return false;
+        }
+        let Some(mut links) = order
+            .iter()
+            .map(|reference| self.membership(list, *reference))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        links.sort_unstable();
+        links.dedup();
+        if links.len() != order.len() {
+            return false;
+        }
+        let mut current = self.lists.get(list.0).and_then(|head| head.head);
+        for reference in order {
+            let link = current.expect("Lists to be as long as their len");
+            self.link_at_mut(link).value = reference;
+            self.set_membership(list, reference, link);
+            current = Some(self.link_at(link).next);
+        }
+        true
+    }
+
+    /// Relinks `list` so that its `i`th value is the one that was at position `permutation[i]`,
+    /// e.g. to apply the result of sorting the keys of a list in parallel. The values don't move and keep their other lists.
+    /// Returns false and leaves `list` unchanged if `permutation` isn't a permutation of `0..list_len`.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<char> = MultiList::new();
+    /// let list = lists.create_list();
+    /// for value in ['c', 'a', 'b'] {
+    ///     let reference = lists.insert(value);
+    ///     lists.push_back(list, reference);
+    /// }
+    ///
+    /// assert!(lists.reorder(list, &[1, 2, 0]));
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<String>(), "abc");
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of `list`
+    pub fn reorder(&mut self, list: ListId, permutation: &[usize]) -> bool {
+        let len = self.list_len(list);
+        if permutation.len() != len {
+            return false;
+        }
+        let mut seen = vec![false; len];
+        for &position in permutation {
+            if position >= len || std::mem::replace(&mut seen[position], true) {
+                return false;
+            }
+        }
+        if len == 0 {
+            return true;
+        }
+        let mut links = Vec::with_capacity(len);
+        let mut current = self.lists[list.0]
+            .head
+            .expect("Lists with members to have a head");
+        for _ in 0..len {
+            links.push(current);
+            current = self.link_at(current).next;
+        }
+        for (i, &position) in permutation.iter().enumerate() {
+            let link = self.link_at_mut(links[position]);
+            link.prev = links[permutation[(i + len - 1) % len]];
+            link.next = links[permutation[(i + 1) % len]];
+        }
+        self.lists[list.0].head = Some(links[permutation[0]]);
+        true
+    }
+
+    /// Swaps the value at `reference` with its neighbour towards the front of `list`, e.g. to bump an entry of a frecency list.
+    /// Returns false if it isn't a member of `list` or already its front.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value and its neighbour are members of
+    pub fn move_toward_front(&mut self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        let Some(link) = self.membership(list, reference.into()) else {
+            return false;
+        };
+        if self.lists[list.0].head == Some(link) {
+            return false;
+        }
+        let prev = self.link_at(link).prev;
+        self.swap_link_values(list, prev, link);
+        true
+    }
+
+    /// Swaps the value at `reference` with its neighbour towards the back of `list`.
+    /// Returns false if it isn't a member of `list` or already its back.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the value and its neighbour are members of
+    pub fn move_toward_back(&mut self, list: ListId, reference: impl Into<ValueRef<T>>) -> bool {
+        let Some(link) = self.membership(list, reference.into()) else {
+            return false;
+        };
+        let next = self.link_at(link).next;
+        if self.lists[list.0].head == Some(next) {
+            return false;
+        }
+        self.swap_link_values(list, link, next);
+        true
+    }
+
+    /// Takes `link` out of the ring of `list` without freeing it, so it can be linked in again with [`MultiList::attach_after`].
+    fn detach(&mut self, list: ListId, link: ValueRef<Link<T>>) {
+        let Link { prev, next, .. } = *self.link_at(link);
+        self.link_at_mut(prev).next = next;
+        self.link_at_mut(next).prev = prev;
+        let head = &mut self.lists[list.0];
+        if head.head == Some(link) {
+            head.head = Some(next);
+        }
+    }
+
+    /// Links the detached `link` into its ring right after `prev`.
+    fn attach_after(&mut self, link: ValueRef<Link<T>>, prev: ValueRef<Link<T>>) {
+        let next = self.link_at(prev).next;
+        let new = self.link_at_mut(link);
+        (new.prev, new.next) = (prev, next);
+        self.link_at_mut(prev).next = link;
+        self.link_at_mut(next).prev = link;
+    }
+
+    /// Moves the value at `reference` in front of the value at `target` in `list`, e.g. to promote an LRU entry.
+    /// Only the links change: the value stays at its position and keeps its other lists.
+    /// Returns false if either isn't a member of `list` or both are the same.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<&str> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let refs: Vec<_> = ["a", "b", "c"].into_iter().map(|value| lists.insert(value)).collect();
+    /// for reference in &refs {
+    ///     lists.push_back(list, *reference);
+    /// }
+    ///
+    /// assert!(lists.move_before(list, refs[2], refs[0]));
+    /// assert!(lists.move_after(list, refs[0], refs[1]));
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec!["c", "b", "a"]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the values are members of
+    pub fn move_before(
+        &mut self,
+        list: ListId,
+        reference: impl Into<ValueRef<T>>,
+        target: impl Into<ValueRef<T>>,
+    ) -> bool {
+        let (Some(link), Some(target)) = (
+            self.membership(list, reference.into()),
+            self.membership(list, target.into()),
+        ) else {
+            return false;
+        };
+        if link == target {
+            return false;
+        }
+        self.detach(list, link);
+        self.attach_after(link, self.link_at(target).prev);
+        let head = &mut self.lists[list.0];
+        if head.head == Some(target) {
+            head.head = Some(link);
+        }
+        true
+    }
+
+    /// Moves the value at `reference` behind the value at `target` in `list`, see [`MultiList::move_before`].
+    /// Returns false if either isn't a member of `list` or both are the same.
+    ///
+    /// # Complexity
+    /// `O(m)` where `m` is the number of lists the values are members of
+    pub fn move_after(
+        &mut self,
+        list: ListId,
+        reference: impl Into<ValueRef<T>>,
+        target: impl Into<ValueRef<T>>,
+    ) -> bool {
+        let (Some(link), Some(target)) = (
+            self.membership(list, reference.into()),
+            self.membership(list, target.into()),
+        ) else {
+            return false;
+        };
+        if link == target {
+            return false;
+        }
+        self.detach(list, link);
+        self.attach_after(link, target);
+        true
+    }
+
+    /// Searches the sorted `list` for `value`, walking from `hint` or else from the front.
+    /// Returns the position of a matching value, or `Err` with the value it would have to be inserted in front of
+    /// ([`None`] for the back). A hint close to the searched value makes the walk short.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<u32> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let refs: Vec<_> = [1, 3, 5, 7].into_iter().map(|value| lists.insert(value)).collect();
+    /// for reference in &refs {
+    ///     lists.push_back(list, *reference);
+    /// }
+    ///
+    /// assert_eq!(lists.search_sorted(list, &5, None), Ok(refs[2]));
+    /// assert_eq!(lists.search_sorted(list, &4, Some(refs[3])), Err(Some(refs[2])));
+    /// assert_eq!(lists.search_sorted(list, &8, Some(refs[1])), Err(None));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(d + m)` where `d` is the distance between the hint and the result and `m` the number of lists the hint is a member of
+    pub fn search_sorted(
+        &self,
+        list: ListId,
+        value: &T,
+        hint: Option<ValueRef<T>>,
+    ) -> Result<ValueRef<T>, Option<ValueRef<T>>>
+    where
+        T: Ord,
+    {
+        let Some(head) = self.lists.get(list.0).and_then(|head| head.head) else {
+            return Err(None);
+        };
+        let value_at = |link| {
+            let reference = self.link_at(link).value;
+            let stored = self.values.get(reference).expect("Members to be stored");
+            (reference, stored.cmp(value))
+        };
+        let mut current = hint
+            .and_then(|hint| self.membership(list, hint))
+            .unwrap_or(head);
+
+        if value_at(current).1 == Ordering::Less {
+            // walk towards the back until a value isn't less
+            loop {
+                current = self.link_at(current).next;
+                if current == head {
+                    return Err(None);
+                }
+                match value_at(current) {
+                    (_, Ordering::Less) => {}
+                    (reference, Ordering::Equal) => return Ok(reference),
+                    (reference, Ordering::Greater) => return Err(Some(reference)),
+                }
+            }
+        }
+        // walk towards the front while the values are greater
+        loop {
+            match value_at(current) {
+                (reference, Ordering::Equal) => return Ok(reference),
+                (_, Ordering::Less) => return Err(Some(value_at(self.link_at(current).next).0)),
+                (reference, Ordering::Greater) if current == head => return Err(Some(reference)),
+                (_, Ordering::Greater) => current = self.link_at(current).prev,
+            }
+        }
+    }
+
+    /// Calls `f` for the values of `list` from front to back, until it returns [`ControlFlow::Break`].
+    /// Returns the break value, or [`None`] if `f` never broke. See [`ValuePool::for_each_until`].
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of `list`
+    #[inline]
+    pub fn for_each_until<B>(
+        &self,
+        list: ListId,
+        mut f: impl FnMut(ValueRef<T>, &T) -> ControlFlow<B>,
+    ) -> Option<B> {
+        match self
+            .iter(list)
+            .try_for_each(|(reference, value)| f(reference, value))
+        {
+            ControlFlow::Break(value) => Some(value),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    /// Iterates over the values of `list` from front to back.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of `list`
+    pub fn iter(&self, list: ListId) -> impl Iterator<Item = (ValueRef<T>, &T)> {
+        let head = self.lists.get(list.0);
+        let mut current = head.and_then(|head| head.head);
+        (0..head.map_or(0, |head| head.len)).filter_map(move |_| {
+            let link = self.link_at(current?);
+            current = Some(link.next);
+            Some((link.value, self.values.get(link.value)?))
+        })
+    }
+
+    /// Iterates over the values of `list` from `reference` to the back, without walking there from the front first.
+    /// Yields nothing if the value at `reference` isn't a member of `list`.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<u32> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let refs: Vec<_> = (0..4).map(|value| lists.insert(value)).collect();
+    /// for reference in &refs {
+    ///     lists.push_back(list, *reference);
+    /// }
+    ///
+    /// assert_eq!(lists.iter_from(list, refs[2]).map(|(_, value)| *value).collect::<Vec<_>>(), vec![2, 3]);
+    /// assert_eq!(lists.iter_from_rev(list, refs[2]).map(|(_, value)| *value).collect::<Vec<_>>(), vec![2, 1, 0]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(m)` to start, where `m` is the number of lists the value is a member of
+    pub fn iter_from(
+        &self,
+        list: ListId,
+        reference: impl Into<ValueRef<T>>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &T)> {
+        let head = self.lists.get(list.0).and_then(|head| head.head);
+        let mut current = self.membership(list, reference.into());
+        std::iter::from_fn(move || {
+            let link = self.link_at(current?);
+            current = Some(link.next).filter(|next| Some(*next) != head);
+            Some((link.value, self.values.get(link.value)?))
+        })
+    }
+
+    /// Iterates over the values of `list` from `reference` to the front, see [`MultiList::iter_from`].
+    ///
+    /// # Complexity
+    /// `O(m)` to start, where `m` is the number of lists the value is a member of
+    pub fn iter_from_rev(
+        &self,
+        list: ListId,
+        reference: impl Into<ValueRef<T>>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &T)> {
+        let head = self.lists.get(list.0).and_then(|head| head.head);
+        let mut current = self.membership(list, reference.into());
+        std::iter::from_fn(move || {
+            let at = current?;
+            let link = self.link_at(at);
+            current = Some(link.prev).filter(|_| Some(at) != head);
+            Some((link.value, self.values.get(link.value)?))
+        })
+    }
+
+    /// Returns a read-only view of `list` with front and back swapped, see [`ReversedList`].
+    #[inline]
+    pub fn reversed(&self, list: ListId) -> ReversedList<'_, T> {
+        ReversedList { lists: self, list }
+    }
+
+    /// Splits the values of `list` into two disjoint halves of mutable borrows, both from front to back:
+    /// The values before `reference`, and `reference` with the values after it.
+    /// The halves can be processed in parallel, e.g. with scoped threads.
+    /// Returns [`None`] if the value at `reference` isn't a member of `list`.
+    /// ```
+    /// use value_pool::multi_list::MultiList;
+    ///
+    /// let mut lists: MultiList<u32> = MultiList::new();
+    /// let list = lists.create_list();
+    /// let refs: Vec<_> = (0..4).map(|value| lists.insert(value)).collect();
+    /// for reference in &refs {
+    ///     lists.push_back(list, *reference);
+    /// }
+    ///
+    /// let (front, back) = lists.split_mut(list, refs[2]).unwrap();
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| front.into_iter().for_each(|(_, value)| *value += 10));
+    ///     s.spawn(|| back.into_iter().for_each(|(_, value)| *value += 20));
+    /// });
+    /// assert_eq!(lists.iter(list).map(|(_, value)| *value).collect::<Vec<_>>(), vec![10, 11, 22, 23]);
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(N + m)` where `N` is the number of positions of the value pool and `m` the number of lists the value is a member of
+    pub fn split_mut(
+        &mut self,
+        list: ListId,
+        reference: impl Into<ValueRef<T>>,
+    ) -> Option<(SplitHalf<'_, T>, SplitHalf<'_, T>)> {
+        let split = self.membership(list, reference.into())?;
+        // the position of every member along `list`, by index of the value
+        let mut positions = vec![None; self.values.store.len()];
+        let len = self.lists[list.0].len;
+        let mut mid = 0;
+        let mut current = self.lists[list.0]
+            .head
+            .expect("Lists with members to have a head");
+        for position in 0..len {
+            if current == split {
+                mid = position;
+            }
+            let link = self.link_at(current);
+            positions[link.value.index.get()] = Some(position);
+            current = link.next;
+        }
+
+        let mut ordered: Vec<_> = (0..len).map(|_| None).collect();
+        for (index, value) in self.values.store.iter_mut().enumerate() {
+            if let (Some(position), Some(value)) = (positions[index], value) {
+                ordered[position] = Some((ValueRef::new(index), value));
+            }
+        }
+        let mut front: Vec<_> = ordered.into_iter().flatten().collect();
+        let back = front.split_off(mid);
+        Some((front, back))
+    }
+}
+
+/// A borrowed view of one list of a [`MultiList<T>`] that presents it back to front, without changing the list.
+/// APIs expecting "most recent first" can share the list with the ones expecting "oldest first". Returned by [`MultiList::reversed`].
+/// ```
+/// use value_pool::multi_list::MultiList;
+///
+/// let mut lists: MultiList<u32> = MultiList::new();
+/// let history = lists.create_list();
+/// let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+/// for reference in &refs {
+///     lists.push_back(history, *reference);
+/// }
+///
+/// let latest_first = lists.reversed(history);
+/// assert_eq!(latest_first.front(), Some(refs[2]));
+/// assert_eq!(latest_first.iter().map(|(_, value)| *value).collect::<Vec<_>>(), vec![2, 1, 0]);
+/// assert_eq!(latest_first.iter_from(refs[1]).map(|(_, value)| *value).collect::<Vec<_>>(), vec![1, 0]);
+/// ```
+#[derive(Debug)]
+pub struct ReversedList<'a, T> {
+    lists: &'a MultiList<T>,
+    list: ListId,
+}
+
+impl<T> Clone for ReversedList<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ReversedList<'_, T> {}
+
+impl<'a, T> ReversedList<'a, T> {
+    /// Returns the number of values in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lists.list_len(self.list)
+    }
+
+    /// Returns true if the list contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_list_empty(self.list)
+    }
+
+    /// Returns true if the value at `reference` is a member of the list, see [`MultiList::contains`].
+    #[inline]
+    pub fn contains(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        self.lists.contains(self.list, reference)
+    }
+
+    /// Returns the last value of the list.
+    #[inline]
+    pub fn front(&self) -> Option<ValueRef<T>> {
+        self.lists.back(self.list)
+    }
+
+    /// Returns the first value of the list.
+    #[inline]
+    pub fn back(&self) -> Option<ValueRef<T>> {
+        self.lists.front(self.list)
+    }
+
+    /// Iterates over the values of the list from back to front.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the list
+    pub fn iter(&self) -> impl Iterator<Item = (ValueRef<T>, &'a T)> {
+        let (lists, list) = (self.lists, self.list);
+        self.front()
+            .into_iter()
+            .flat_map(move |front| lists.iter_from_rev(list, front))
+    }
+
+    /// Iterates from `reference` towards the first value of the list, see [`MultiList::iter_from_rev`].
+    ///
+    /// # Complexity
+    /// `O(m)` to start, where `m` is the number of lists the value is a member of
+    #[inline]
+    pub fn iter_from(
+        &self,
+        reference: impl Into<ValueRef<T>>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &'a T)> {
+        self.lists.iter_from_rev(self.list, reference)
+    }
+
+    /// Iterates from `reference` towards the last value of the list, see [`MultiList::iter_from`].
+    ///
+    /// # Complexity
+    /// `O(m)` to start, where `m` is the number of lists the value is a member of
+    #[inline]
+    pub fn iter_from_rev(
+        &self,
+        reference: impl Into<ValueRef<T>>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &'a T)> {
+        self.lists.iter_from(self.list, reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiList;
+    use crate::ValuePool;
+    use std::ops::ControlFlow;
+
+    #[test]
+    fn test_swap_values_keeps_pinned_values() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        let (a, b) = (pool.push(1), pool.push(2));
+        pool.pin_slot(a);
+        let (mut lists, list) = MultiList::from_order(pool, [a, b]);
+        assert!(!lists.swap_values(a, b));
+        assert_eq!(lists.get(a), Some(&1));
+        assert_eq!(
+            lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_retain_removes_from_all_lists() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..5).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_back(a, *reference);
+        }
+        lists.push_back(b, refs[1]);
+        lists.push_back(b, refs[2]);
+
+        let removed = lists.retain(a, |_, value| *value != 1 && *value != 3);
+        assert_eq!(removed, vec![(refs[1], 1), (refs[3], 3)]);
+        assert_eq!(
+            lists.iter(a).map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![0, 2, 4]
+        );
+        assert_eq!(lists.iter(b).map(|(_, v)| *v).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(lists.get(refs[1]), None);
+        assert!(lists.retain(b, |_, _| true).is_empty());
+    }
+
+    #[test]
+    fn test_reversed_list() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let list = lists.create_list();
+        let empty = lists.create_list();
+        let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_back(list, *reference);
+        }
+        let reversed = lists.reversed(list);
+        assert_eq!((reversed.len(), reversed.back()), (3, Some(refs[0])));
+        assert_eq!(
+            reversed
+                .iter_from_rev(refs[1])
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(reversed.contains(refs[0]));
+        assert!(lists.reversed(empty).is_empty());
+        assert_eq!(lists.reversed(empty).iter().count(), 0);
+    }
+
+    #[test]
+    fn test_rings_stay_linked() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..4).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            assert!(lists.push_back(a, *reference));
+        }
+        assert!(!lists.push_back(a, refs[0]));
+        assert!(lists.push_front(b, refs[2]));
+        assert!(lists.push_front(b, refs[1]));
+
+        assert!(lists.unlink(a, refs[2]));
+        assert!(!lists.unlink(a, refs[2]));
+        assert_eq!(lists.pop_back(a), Some(refs[3]));
+        let values =
+            |lists: &MultiList<u32>, list| lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(values(&lists, a), vec![0, 1]);
+        assert_eq!(values(&lists, b), vec![1, 2]);
+
+        assert_eq!(lists.remove(refs[1]), Some(1));
+        assert_eq!((values(&lists, a), values(&lists, b)), (vec![0], vec![2]));
+        assert_eq!(
+            (lists.front(b), lists.back(b)),
+            (Some(refs[2]), Some(refs[2]))
+        );
+        assert!(lists.contains(b, refs[2]) && !lists.contains(b, refs[0]));
+        assert_eq!(lists.list_len(a), 1);
+        let found = lists.for_each_until(b, |reference, value| {
+            if *value == 2 {
+                ControlFlow::Break(reference)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(found, Some(refs[2]));
+    }
+
+    #[test]
+    fn test_move_toward_neighbors() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_back(a, *reference);
+            lists.push_front(b, *reference);
+        }
+        assert!(!lists.move_toward_front(a, refs[0]));
+        assert!(!lists.move_toward_back(a, refs[2]));
+        assert!(lists.move_toward_front(a, refs[2]));
+        assert!(lists.move_toward_back(b, refs[2]));
+        let values =
+            |lists: &MultiList<u32>, list| lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(
+            (values(&lists, a), values(&lists, b)),
+            (vec![0, 2, 1], vec![1, 2, 0])
+        );
+
+        // the memberships follow the moved values
+        assert!(lists.unlink(a, refs[2]));
+        assert_eq!(lists.remove(refs[1]), Some(1));
+        assert_eq!(
+            (values(&lists, a), values(&lists, b)),
+            (vec![0], vec![2, 0])
+        );
+
+        assert!(!lists.reorder_to(b, [refs[2], refs[2]]));
+        assert!(!lists.reorder_to(b, [refs[2]]));
+        assert!(lists.reorder_to(b, [refs[0], refs[2]]));
+        assert!(lists.move_toward_back(b, refs[0]));
+        assert_eq!(values(&lists, b), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_move_before_after() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..4).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_back(a, *reference);
+        }
+        lists.push_back(b, refs[0]);
+        lists.push_back(b, refs[1]);
+        assert!(!lists.move_before(a, refs[1], refs[1]));
+        assert!(!lists.move_after(b, refs[2], refs[0]));
+
+        let values =
+            |lists: &MultiList<u32>, list| lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert!(lists.move_after(a, refs[0], refs[3]));
+        assert_eq!(values(&lists, a), vec![1, 2, 3, 0]);
+        assert!(lists.move_before(a, refs[3], refs[1]));
+        assert_eq!(values(&lists, a), vec![3, 1, 2, 0]);
+        assert!(lists.move_before(b, refs[1], refs[0]));
+        assert_eq!(values(&lists, b), vec![1, 0]);
+        assert_eq!(
+            (lists.front(a), lists.back(a)),
+            (Some(refs[3]), Some(refs[0]))
+        );
+
+        // the links stay with their values
+        assert!(lists.unlink(a, refs[1]));
+        assert_eq!(lists.remove(refs[3]), Some(3));
+        assert_eq!(
+            (values(&lists, a), values(&lists, b)),
+            (vec![2, 0], vec![1, 0])
+        );
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        assert!(lists.reorder(a, &[]));
+        let refs: Vec<_> = (0..4).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_back(a, *reference);
+            lists.push_front(b, *reference);
+        }
+        assert!(!lists.reorder(a, &[0, 1, 2]));
+        assert!(!lists.reorder(a, &[0, 1, 2, 4]));
+        assert!(!lists.reorder(a, &[0, 1, 1, 2]));
+
+        let values =
+            |lists: &MultiList<u32>, list| lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert!(lists.reorder(a, &[3, 0, 2, 1]));
+        assert_eq!(values(&lists, a), vec![3, 0, 2, 1]);
+        assert_eq!(
+            (lists.front(a), lists.back(a)),
+            (Some(refs[3]), Some(refs[1]))
+        );
+        assert!(lists.move_toward_back(a, refs[0]));
+        assert!(lists.unlink(a, refs[2]));
+        assert_eq!(values(&lists, a), vec![3, 0, 1]);
+        assert_eq!(values(&lists, b), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_from_order() {
+        let mut pool = crate::ValuePool::new();
+        let refs: Vec<_> = (0..3).map(|value| pool.push(value)).collect();
+        pool.remove(refs[1]);
+        let (mut lists, list) = MultiList::from_order(pool, [refs[2], refs[1], refs[2], refs[0]]);
+        assert_eq!(
+            lists
+                .iter(list)
+                .map(|(reference, _)| reference)
+                .collect::<Vec<_>>(),
+            vec![refs[2], refs[0]]
+        );
+        assert_eq!(lists.insert(5), refs[1]);
+        assert!(lists.push_front(list, refs[1]));
+        assert_eq!(lists.list_len(list), 3);
+
+        let pool = lists.into_pool();
+        assert_eq!(pool.element_count(), 3);
+        assert_eq!(pool.get(refs[1]), Some(&5));
+    }
+
+    #[test]
+    fn test_search_sorted() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let list = lists.create_list();
+        assert_eq!(lists.search_sorted(list, &1, None), Err(None));
+        let refs: Vec<_> = [2, 4, 4, 6].into_iter().map(|v| lists.insert(v)).collect();
+        for reference in &refs {
+            lists.push_back(list, *reference);
+        }
+        let other = lists.insert(3);
+
+        assert_eq!(
+            lists.search_sorted(list, &1, Some(refs[3])),
+            Err(Some(refs[0]))
+        );
+        assert_eq!(lists.search_sorted(list, &3, None), Err(Some(refs[1])));
+        assert_eq!(
+            lists.search_sorted(list, &3, Some(other)),
+            Err(Some(refs[1]))
+        );
+        assert_eq!(
+            lists.search_sorted(list, &5, Some(refs[0])),
+            Err(Some(refs[3]))
+        );
+        assert_eq!(lists.search_sorted(list, &6, Some(refs[3])), Ok(refs[3]));
+        assert!(
+            matches!(lists.search_sorted(list, &4, None), Ok(r) if r == refs[1] || r == refs[2])
+        );
+        assert_eq!(lists.search_sorted(list, &7, Some(refs[0])), Err(None));
+        assert_eq!(lists.search_sorted(list, &7, Some(refs[3])), Err(None));
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_front(a, *reference);
+        }
+        let values =
+            |iter: &mut dyn Iterator<Item = (_, &u32)>| iter.map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(values(&mut lists.iter_from(a, refs[2])), vec![2, 1, 0]);
+        assert_eq!(values(&mut lists.iter_from(a, refs[0])), vec![0]);
+        assert_eq!(values(&mut lists.iter_from_rev(a, refs[0])), vec![0, 1, 2]);
+        assert_eq!(values(&mut lists.iter_from_rev(a, refs[2])), vec![2]);
+        assert_eq!(lists.iter_from(b, refs[0]).count(), 0);
+        assert_eq!(lists.iter_from_rev(b, refs[0]).count(), 0);
+    }
+
+    #[test]
+    fn test_swap_values() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+        lists.push_back(a, refs[0]);
+        lists.push_back(a, refs[1]);
+        lists.push_back(b, refs[2]);
+        assert_eq!(lists.remove(refs[1]), Some(1));
+        assert!(!lists.swap_values(refs[0], refs[1]));
+
+        assert!(lists.swap_values(refs[0], refs[2]));
+        let values =
+            |lists: &MultiList<u32>, list| lists.iter(list).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!((values(&lists, a), values(&lists, b)), (vec![2], vec![0]));
+        assert!(lists.contains(a, refs[0]) && lists.contains(b, refs[2]));
+    }
+
+    #[test]
+    fn test_split_mut() {
+        let mut lists: MultiList<u32> = MultiList::new();
+        let (a, b) = (lists.create_list(), lists.create_list());
+        let refs: Vec<_> = (0..3).map(|value| lists.insert(value)).collect();
+        for reference in &refs {
+            lists.push_front(a, *reference);
+        }
+        assert!(lists.split_mut(b, refs[0]).is_none());
+
+        let (front, back) = lists.split_mut(a, refs[2]).unwrap();
+        assert!(front.is_empty());
+        assert_eq!(
+            back.iter()
+                .map(|(reference, _)| *reference)
+                .collect::<Vec<_>>(),
+            vec![refs[2], refs[1], refs[0]]
+        );
+        let (front, back) = lists.split_mut(a, refs[0]).unwrap();
+        assert_eq!((front.len(), back.len()), (2, 1));
+    }
+}
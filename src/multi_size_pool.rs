@@ -0,0 +1,220 @@
+//! This module implements [`MultiSizePool<T>`], which hands out [`Vec<T>`] buffers by size class and recycles them by capacity.
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug, Clone)]
+struct SizeClass<T> {
+    capacity: usize,
+    buffers: ValuePool<Vec<T>>,
+    // released buffers with a capacity of at least `capacity`, all empty
+    free: Vec<Vec<T>>,
+}
+
+/// A handle to a buffer of a [`MultiSizePool<T>`], tagged with the size class of the buffer.
+#[derive(Debug)]
+pub struct SizedRef<T> {
+    class: usize,
+    reference: ValueRef<Vec<T>>,
+}
+
+impl<T> SizedRef<T> {
+    /// Returns the index of the size class of this handle. Classes are ordered by capacity, smallest first.
+    #[inline]
+    pub fn class(&self) -> usize {
+        self.class
+    }
+}
+
+impl<T> Clone for SizedRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for SizedRef<T> {}
+
+impl<T> PartialEq for SizedRef<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.class == other.class && self.reference == other.reference
+    }
+}
+impl<T> Eq for SizedRef<T> {}
+
+/// A [`MultiSizePool<T>`] manages [`Vec<T>`] buffers (e.g. `Vec<u8>`) in several size classes, like a size-class allocator.
+/// [`MultiSizePool::allocate`] returns an empty buffer of the smallest class that fits, reusing a released buffer if possible.
+/// [`MultiSizePool::release`] stores a buffer for reuse in the greatest class its capacity still satisfies.
+/// ```
+/// use value_pool::multi_size_pool::MultiSizePool;
+///
+/// let mut buffers: MultiSizePool<u8> = MultiSizePool::new([64, 1024, 16 * 1024]);
+/// let small = buffers.allocate(10).unwrap();
+/// let large = buffers.allocate(1000).unwrap();
+/// assert_eq!((small.class(), large.class()), (0, 1));
+/// assert!(buffers.get(large).unwrap().capacity() >= 1024);
+///
+/// buffers.get_mut(small).unwrap().extend_from_slice(b"hello");
+/// buffers.release(small);
+/// assert_eq!(buffers.free_buffers(0), 1);
+///
+/// // the released buffer gets reused, empty
+/// let reused = buffers.allocate(32).unwrap();
+/// assert!(buffers.get(reused).unwrap().is_empty());
+/// assert_eq!(buffers.free_buffers(0), 0);
+/// assert!(buffers.allocate(100_000).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiSizePool<T> {
+    // ordered by capacity
+    classes: Vec<SizeClass<T>>,
+}
+
+impl<T> MultiSizePool<T> {
+    /// Creates a [`MultiSizePool`] with one size class per capacity in `class_capacities`. Duplicates are ignored.
+    pub fn new(class_capacities: impl IntoIterator<Item = usize>) -> MultiSizePool<T> {
+        let mut capacities: Vec<usize> = class_capacities.into_iter().collect();
+        capacities.sort_unstable();
+        capacities.dedup();
+        MultiSizePool {
+            classes: (capacities
+                .into_iter()
+                .map(|capacity| SizeClass {
+                    capacity,
+                    buffers: (ValuePool::new()),
+                    free: (Vec::new()),
+                })
+                .collect()),
+        }
+    }
+
+    /// Returns the number of size classes.
+    #[inline]
+    pub fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Returns the capacity of size class `class`.
+    #[inline]
+    pub fn class_capacity(&self, class: usize) -> Option<usize> {
+        Some(self.classes.get(class)?.capacity)
+    }
+
+    /// Returns the number of allocated buffers across all classes.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.classes
+            .iter()
+            .map(|class| class.buffers.element_count())
+            .sum()
+    }
+
+    /// Returns the number of released buffers of class `class` that are ready for reuse.
+    #[inline]
+    pub fn free_buffers(&self, class: usize) -> usize {
+        self.classes.get(class).map_or(0, |class| class.free.len())
+    }
+
+    /// Returns an empty buffer with a capacity of at least `min_capacity`, from the smallest class that fits.
+    /// Returns [`None`] if `min_capacity` is greater than the capacity of every class.
+    ///
+    /// # Complexity
+    /// `O(log c)` where `c` is the number of classes, plus the allocation if no released buffer is available
+    pub fn allocate(&mut self, min_capacity: usize) -> Option<SizedRef<T>> {
+        let index = self
+            .classes
+            .partition_point(|class| class.capacity < min_capacity);
+        let class = self.classes.get_mut(index)?;
+        let buffer = class
+            .free
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(class.capacity));
+        Some(SizedRef {
+            class: index,
+            reference: (class.buffers.push(buffer)),
+        })
+    }
+
+    /// Gets a borrow of the buffer at `reference`.
+    #[inline]
+    pub fn get(&self, reference: SizedRef<T>) -> Option<&Vec<T>> {
+        self.classes
+            .get(reference.class)?
+            .buffers
+            .get(reference.reference)
+    }
+
+    /// Gets a mut borrow of the buffer at `reference`. Growing the buffer beyond its class is fine,
+    /// [`MultiSizePool::release`] sorts it by its capacity.
+    #[inline]
+    pub fn get_mut(&mut self, reference: SizedRef<T>) -> Option<&mut Vec<T>> {
+        self.classes
+            .get_mut(reference.class)?
+            .buffers
+            .get_mut(reference.reference)
+    }
+
+    /// Removes the buffer at `reference` and returns it, without recycling it.
+    #[inline]
+    pub fn take(&mut self, reference: SizedRef<T>) -> Option<Vec<T>> {
+        self.classes
+            .get_mut(reference.class)?
+            .buffers
+            .take(reference.reference)
+    }
+
+    /// Clears the buffer at `reference` and keeps it for reuse, in the greatest class whose capacity it still satisfies.
+    /// Buffers that shrank below the smallest class are dropped. Returns false if no buffer is stored at `reference`.
+    ///
+    /// # Complexity
+    /// `O(log c)` where `c` is the number of classes, plus clearing the buffer
+    pub fn release(&mut self, reference: SizedRef<T>) -> bool {
+        let Some(mut buffer) = self.take(reference) else {
+            return false;
+        };
+        buffer.clear();
+        let fitting = self
+            .classes
+            .partition_point(|class| class.capacity <= buffer.capacity());
+        if let Some(class) = fitting.checked_sub(1) {
+            self.classes[class].free.push(buffer);
+        }
+        true
+    }
+
+    /// Drops all released buffers, to give their memory back.
+    #[inline]
+    pub fn shrink(&mut self) {
+        for class in &mut self.classes {
+            class.free = Vec::new();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiSizePool;
+
+    #[test]
+    fn test_recycle_by_capacity() {
+        let mut pool: MultiSizePool<u32> = MultiSizePool::new([16, 4, 16]);
+        assert_eq!(pool.class_count(), 2);
+        let exact = pool.allocate(4).unwrap();
+        assert_eq!(pool.class_capacity(exact.class()), Some(4));
+
+        // a grown buffer gets recycled into the bigger class
+        pool.get_mut(exact).unwrap().extend(0..20);
+        assert!(pool.release(exact));
+        assert!(!pool.release(exact));
+        assert_eq!((pool.free_buffers(0), pool.free_buffers(1)), (0, 1));
+
+        let shrunk = pool.allocate(1).unwrap();
+        *pool.get_mut(shrunk).unwrap() = Vec::new();
+        pool.release(shrunk);
+        assert_eq!(pool.free_buffers(0), 0);
+
+        let taken = pool.allocate(16).unwrap();
+        assert_eq!(pool.element_count(), 1);
+        assert!(pool.take(taken).unwrap().capacity() >= 20);
+        pool.shrink();
+        assert_eq!(pool.free_buffers(1), 0);
+    }
+}
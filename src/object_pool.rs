@@ -0,0 +1,171 @@
+//! This module implements [`ObjectPool<T>`], a recycler for objects that are expensive to construct (e.g. buffers).
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug)]
+struct FreeSet<T> {
+    objects: ValuePool<T>,
+    // positions in `objects` that hold a value
+    available: Vec<ValueRef<T>>,
+}
+
+/// An [`ObjectPool<T>`] hands out objects trough [`ObjectPool::acquire`]. Instead of being dropped, a released object
+/// gets reset and stored for the next [`acquire`](ObjectPool::acquire). New objects are only created with [`Default`]
+/// if no released one is available.
+/// ```
+/// use value_pool::object_pool::ObjectPool;
+///
+/// let buffers: ObjectPool<Vec<u8>> = ObjectPool::new(Vec::clear);
+/// {
+///     let mut buffer = buffers.acquire();
+///     buffer.extend_from_slice(b"hello");
+///     assert_eq!(buffers.available(), 0);
+/// } // `buffer` gets reset and returned
+/// assert_eq!(buffers.available(), 1);
+///
+/// let buffer = buffers.acquire();
+/// assert!(buffer.is_empty());
+/// assert!(buffer.capacity() >= 5); // the allocation got reused
+/// ```
+#[derive(Debug)]
+pub struct ObjectPool<T: Default> {
+    free: RefCell<FreeSet<T>>,
+    reset: fn(&mut T),
+}
+
+impl<T: Default> Default for ObjectPool<T> {
+    fn default() -> Self {
+        Self::new(|_| {})
+    }
+}
+
+impl<T: Default> ObjectPool<T> {
+    /// Creates a new, empty [`ObjectPool`]. `reset` gets called on every released object.
+    #[inline]
+    pub fn new(reset: fn(&mut T)) -> ObjectPool<T> {
+        ObjectPool {
+            free: (RefCell::new(FreeSet {
+                objects: (ValuePool::new()),
+                available: (Vec::new()),
+            })),
+            reset,
+        }
+    }
+
+    /// Returns the number of released objects that are ready to be acquired.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.free.borrow().available.len()
+    }
+
+    /// Returns a released object or a new one, if none is available.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn acquire(&self) -> Pooled<'_, T> {
+        let object = {
+            let mut free = self.free.borrow_mut();
+            let free = &mut *free;
+            free.available
+                .pop()
+                .and_then(|reference| free.objects.take(reference))
+        };
+        Pooled {
+            pool: self,
+            object: Some(object.unwrap_or_default()),
+        }
+    }
+
+    /// Resets `object` and stores it for later use.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn release(&self, mut object: T) {
+        (self.reset)(&mut object);
+        let mut free = self.free.borrow_mut();
+        let reference = free.objects.push(object);
+        free.available.push(reference);
+    }
+
+    /// Drops all released objects.
+    #[inline]
+    pub fn clear(&self) {
+        let mut free = self.free.borrow_mut();
+        free.objects.clear();
+        free.available.clear();
+    }
+}
+
+/// An object acquired from an [`ObjectPool<T>`]. It gets released back into the pool on drop.
+#[derive(Debug)]
+pub struct Pooled<'a, T: Default> {
+    pool: &'a ObjectPool<T>,
+    // `None` only after `detach`
+    object: Option<T>,
+}
+
+impl<T: Default> Pooled<'_, T> {
+    /// Takes the object out of the [`ObjectPool`], it won't be released.
+    #[inline]
+    pub fn detach(mut self) -> T {
+        self.object
+            .take()
+            .expect("Object to exist until the guard gets dropped")
+    }
+}
+
+impl<T: Default> Deref for Pooled<'_, T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.object
+            .as_ref()
+            .expect("Object to exist until the guard gets dropped")
+    }
+}
+
+impl<T: Default> DerefMut for Pooled<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.object
+            .as_mut()
+            .expect("Object to exist until the guard gets dropped")
+    }
+}
+
+impl<T: Default> Drop for Pooled<'_, T> {
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take() {
+            self.pool.release(object);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectPool;
+
+    #[test]
+    fn test_release_and_detach() {
+        let pool: ObjectPool<String> = ObjectPool::new(String::clear);
+        let mut first = pool.acquire();
+        let mut second = pool.acquire();
+        first.push_str("first");
+        second.push_str("second");
+        drop(first);
+        assert_eq!(second.detach(), "second");
+        assert_eq!(pool.available(), 1);
+
+        assert_eq!(*pool.acquire(), "");
+        pool.release("unused".to_string());
+        assert_eq!(pool.available(), 2);
+        pool.clear();
+        assert_eq!(pool.available(), 0);
+    }
+}
@@ -0,0 +1,256 @@
+//! This module implements [`ObservedPool<T, S>`], a [`ValuePool<T>`] that reports every mutation as a [`PoolEvent<T>`] to an [`EventSink<T>`].
+//!
+//! Compared to [`SmartValuePool`](crate::smart_value_pool::SmartValuePool), which only reacts to a pool becoming empty,
+//! this is meant for observer architectures where other parts of a program need to follow all changes.
+use std::sync::mpsc::{Sender, SyncSender};
+
+use crate::{ValuePool, ValueRef};
+
+/// A mutation of an [`ObservedPool<T, S>`].
+#[derive(Debug)]
+pub enum PoolEvent<T> {
+    /// A value got pushed to the position.
+    Pushed(ValueRef<T>),
+    /// The value at the position got taken or removed.
+    Removed(ValueRef<T>),
+    /// The value at the position got modified or replaced.
+    Modified(ValueRef<T>),
+}
+
+impl<T> PoolEvent<T> {
+    /// Returns the position the event is about.
+    #[inline]
+    pub fn reference(&self) -> ValueRef<T> {
+        match self {
+            PoolEvent::Pushed(reference)
+            | PoolEvent::Removed(reference)
+            | PoolEvent::Modified(reference) => *reference,
+        }
+    }
+}
+
+impl<T> Clone for PoolEvent<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for PoolEvent<T> {}
+
+impl<T> PartialEq for PoolEvent<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PoolEvent::Pushed(a), PoolEvent::Pushed(b))
+            | (PoolEvent::Removed(a), PoolEvent::Removed(b))
+            | (PoolEvent::Modified(a), PoolEvent::Modified(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+impl<T> Eq for PoolEvent<T> {}
+
+/// Receives the [`PoolEvent<T>`]s of an [`ObservedPool<T, S>`].
+///
+/// Implemented for [`Sender`] and [`SyncSender`] (events sent to a disconnected receiver are dropped),
+/// [`Vec`] (collects all events) and boxed closures.
+pub trait EventSink<T> {
+    /// Called right after the mutation described by `event` happened.
+    fn send(&mut self, event: PoolEvent<T>);
+}
+
+impl<T> EventSink<T> for Sender<PoolEvent<T>> {
+    #[inline]
+    fn send(&mut self, event: PoolEvent<T>) {
+        let _ = Sender::send(self, event);
+    }
+}
+
+impl<T> EventSink<T> for SyncSender<PoolEvent<T>> {
+    #[inline]
+    fn send(&mut self, event: PoolEvent<T>) {
+        let _ = SyncSender::send(self, event);
+    }
+}
+
+impl<T> EventSink<T> for Vec<PoolEvent<T>> {
+    #[inline]
+    fn send(&mut self, event: PoolEvent<T>) {
+        self.push(event);
+    }
+}
+
+impl<T> EventSink<T> for Box<dyn FnMut(PoolEvent<T>) + '_> {
+    #[inline]
+    fn send(&mut self, event: PoolEvent<T>) {
+        self(event)
+    }
+}
+
+impl<T> EventSink<T> for Box<dyn FnMut(PoolEvent<T>) + Send + '_> {
+    #[inline]
+    fn send(&mut self, event: PoolEvent<T>) {
+        self(event)
+    }
+}
+
+/// An [`ObservedPool<T, S>`] wraps a [`ValuePool<T>`] and sends a [`PoolEvent<T>`] to its sink `S` after every mutation.
+/// Mutable access is only given trough [`ObservedPool::update`], so events are never sent before the change happened.
+/// ```
+/// use std::sync::mpsc;
+/// use value_pool::observed_pool::{ObservedPool, PoolEvent};
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let mut pool: ObservedPool<u32, _> = ObservedPool::new(sender);
+///
+/// let reference = pool.push(1);
+/// pool.update(reference, |value| *value += 1);
+/// pool.take(reference);
+///
+/// assert_eq!(
+///     receiver.try_iter().collect::<Vec<_>>(),
+///     vec![PoolEvent::Pushed(reference), PoolEvent::Modified(reference), PoolEvent::Removed(reference)]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ObservedPool<T, S: EventSink<T>> {
+    pool: ValuePool<T>,
+    sink: S,
+}
+
+impl<T, S: EventSink<T>> ObservedPool<T, S> {
+    /// Creates a new, empty [`ObservedPool`] that sends its events to `sink`.
+    #[inline]
+    pub fn new(sink: S) -> ObservedPool<T, S> {
+        ObservedPool::with_pool(ValuePool::new(), sink)
+    }
+
+    /// Wraps `pool`. Values already stored don't produce events.
+    #[inline]
+    pub fn with_pool(pool: ValuePool<T>, sink: S) -> ObservedPool<T, S> {
+        ObservedPool { pool, sink }
+    }
+
+    /// Gives read-only access to the underlying [`ValuePool`].
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<T> {
+        &self.pool
+    }
+
+    /// Returns the sink.
+    #[inline]
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns the sink mutably, e.g. to drain collected events.
+    #[inline]
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Returns the underlying [`ValuePool`] and the sink.
+    #[inline]
+    pub fn into_parts(self) -> (ValuePool<T>, S) {
+        (self.pool, self.sink)
+    }
+
+    /// Returns the number of elements stored.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.pool.element_count()
+    }
+
+    /// Returns true if no elements are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Same as [`ValuePool::get`].
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.pool.get(reference)
+    }
+
+    /// Same as [`ValuePool::push`]. Sends [`PoolEvent::Pushed`].
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let reference = self.pool.push(value);
+        self.sink.send(PoolEvent::Pushed(reference));
+        reference
+    }
+
+    /// Calls `f` with a mut borrow of the value at `reference` and sends [`PoolEvent::Modified`] afterwards.
+    /// Returns [`None`] if no value is stored there.
+    #[inline]
+    pub fn update<R>(
+        &mut self,
+        reference: impl Into<ValueRef<T>>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let reference: ValueRef<T> = reference.into();
+        let result = f(self.pool.get_mut(reference)?);
+        self.sink.send(PoolEvent::Modified(reference));
+        Some(result)
+    }
+
+    /// Replaces the value at `reference` with `value` and returns the old one. Sends [`PoolEvent::Modified`].
+    /// Returns `Err(value)` if no value is stored there.
+    #[inline]
+    pub fn replace(&mut self, reference: impl Into<ValueRef<T>>, value: T) -> Result<T, T> {
+        let reference: ValueRef<T> = reference.into();
+        match self.pool.get_mut(reference) {
+            Some(stored) => {
+                let old = std::mem::replace(stored, value);
+                self.sink.send(PoolEvent::Modified(reference));
+                Ok(old)
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Same as [`ValuePool::take`]. Sends [`PoolEvent::Removed`] if a value got taken.
+    #[inline]
+    pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.take(reference)?;
+        self.sink.send(PoolEvent::Removed(reference));
+        Some(value)
+    }
+
+    /// Same as [`ValuePool::remove`]. Sends [`PoolEvent::Removed`] if a value got removed.
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.remove(reference)?;
+        self.sink.send(PoolEvent::Removed(reference));
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ObservedPool, PoolEvent};
+
+    #[test]
+    fn test_callback_sink() {
+        let mut removed = Vec::new();
+        {
+            let sink: Box<dyn FnMut(PoolEvent<u32>)> = Box::new(|event| {
+                if let PoolEvent::Removed(reference) = event {
+                    removed.push(reference);
+                }
+            });
+            let mut pool = ObservedPool::new(sink);
+            let a = pool.push(1);
+            let b = pool.push(2);
+            assert_eq!(pool.replace(a, 3), Ok(1));
+            assert_eq!(pool.remove(b), Some(2));
+            assert_eq!(pool.remove(b), None);
+            assert_eq!(pool.take(b), None);
+        }
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].index.get(), 1);
+    }
+}
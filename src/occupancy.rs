@@ -0,0 +1,129 @@
+//! This module implements [`OccupancySnapshot<T>`] and [`ValuePool::expired_refs_since`], which find refs that became stale since a snapshot.
+use crate::{ref_set::RefSet, ValuePool};
+
+/// Remembers which positions of a [`ValuePool<T>`] were occupied, created by [`ValuePool::occupancy_snapshot`].
+/// It uses one bit per position, so it's cheap to keep around.
+#[derive(Debug)]
+pub struct OccupancySnapshot<T> {
+    occupied: RefSet<T>,
+    #[cfg(feature = "slot-stats")]
+    reuse_counts: Vec<u64>,
+}
+
+impl<T> Clone for OccupancySnapshot<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        OccupancySnapshot {
+            occupied: (self.occupied.clone()),
+            #[cfg(feature = "slot-stats")]
+            reuse_counts: (self.reuse_counts.clone()),
+        }
+    }
+}
+
+impl<T> OccupancySnapshot<T> {
+    /// Returns the positions that were occupied when this snapshot got taken.
+    #[inline]
+    pub fn occupied(&self) -> &RefSet<T> {
+        &self.occupied
+    }
+}
+
+impl<T> ValuePool<T> {
+    /// Takes an [`OccupancySnapshot`] of this [`ValuePool`], to be passed to [`ValuePool::expired_refs_since`] later.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn occupancy_snapshot(&self) -> OccupancySnapshot<T> {
+        OccupancySnapshot {
+            occupied: (self.occupied().map(|(reference, _)| reference).collect()),
+            #[cfg(feature = "slot-stats")]
+            reuse_counts: (self.reuse_counts.clone()),
+        }
+    }
+
+    /// Returns all positions that were occupied in `snapshot` but got freed since.
+    /// Refs to them that were handed out before the snapshot are stale, so long-lived systems can drop them proactively.
+    ///
+    /// Without the `slot-stats` feature, positions that got freed and reused since are not detected,
+    /// because they look occupied in both. With it, they are returned as well.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<&str> = ValuePool::new();
+    /// let a = pool.push("a");
+    /// let b = pool.push("b");
+    /// let snapshot = pool.occupancy_snapshot();
+    ///
+    /// pool.take(a);
+    /// let expired: Vec<_> = pool.expired_refs_since(&snapshot).iter().collect();
+    /// assert_eq!(expired, vec![a]);
+    /// assert!(!pool.expired_refs_since(&snapshot).contains(b));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the number of positions stored in `snapshot`
+    pub fn expired_refs_since(&self, snapshot: &OccupancySnapshot<T>) -> RefSet<T> {
+        snapshot
+            .occupied
+            .iter()
+            .filter(|reference| {
+                !self.has_item(*reference) || self.reused_since(snapshot, reference.index.get())
+            })
+            .collect()
+    }
+
+    /// Returns true if the position `index` got reused since `snapshot`.
+    #[cfg(feature = "slot-stats")]
+    #[inline]
+    fn reused_since(&self, snapshot: &OccupancySnapshot<T>, index: usize) -> bool {
+        let before = snapshot.reuse_counts.get(index).copied().unwrap_or(0);
+        self.reuse_counts.get(index).copied().unwrap_or(0) != before
+    }
+
+    /// Reuses can't be detected without the `slot-stats` feature.
+    #[cfg(not(feature = "slot-stats"))]
+    #[inline]
+    fn reused_since(&self, _snapshot: &OccupancySnapshot<T>, _index: usize) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_expired_refs() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        for value in 0..5 {
+            pool.push(value);
+        }
+        pool.take(ValueRef::new(1));
+        let snapshot = pool.occupancy_snapshot();
+        assert_eq!(snapshot.occupied().len(), 4);
+
+        pool.remove(ValueRef::new(4)); // shrinks the store
+        pool.take(ValueRef::new(2));
+        pool.push(20); // reuses 2
+        let expired: Vec<usize> = pool
+            .expired_refs_since(&snapshot)
+            .iter()
+            .map(|reference| reference.index.get())
+            .collect();
+        if cfg!(feature = "slot-stats") {
+            assert_eq!(expired, vec![2, 4]);
+        } else {
+            assert_eq!(expired, vec![4]);
+        }
+        pool.clear();
+        assert_eq!(pool.expired_refs_since(&snapshot).len(), 4);
+
+        // positions filled again after `clear` are reused as well
+        for value in 0..5 {
+            pool.push(value);
+        }
+        let expired = pool.expired_refs_since(&snapshot).len();
+        assert_eq!(expired, if cfg!(feature = "slot-stats") { 4 } else { 0 });
+    }
+}
@@ -0,0 +1,200 @@
+//! This module implements [`OrderedPoolMap<K, V>`], an ordered map whose `(K, V)` pairs live in a [`ValuePool`].
+use std::{borrow::Borrow, collections::BTreeMap, ops::RangeBounds};
+
+use crate::{
+    keyed_pool::{IndexedPool, PairIndex},
+    ValuePool, ValueRef,
+};
+
+impl<K: Ord, V> PairIndex<K, V> for BTreeMap<K, ValueRef<(K, V)>> {
+    #[inline]
+    fn position(&self, key: &K) -> Option<ValueRef<(K, V)>> {
+        self.get(key).copied()
+    }
+
+    #[inline]
+    fn insert_position(&mut self, key: K, reference: ValueRef<(K, V)>) {
+        self.insert(key, reference);
+    }
+
+    #[inline]
+    fn remove_position(&mut self, key: &K) -> Option<ValueRef<(K, V)>> {
+        self.remove(key)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        BTreeMap::clear(self);
+    }
+}
+
+/// [`OrderedPoolMap<K, V>`] stores `(K, V)` pairs in a [`ValuePool<(K, V)>`] and keeps them ordered by key through a [`BTreeMap<K, ValueRef<(K, V)>>`].
+/// Iteration is in key order, while the [`ValueRef`] returned on insertion gives `O(1)` access to a pair.
+/// It's the ordered sibling of [`KeyedPool<K, V>`](crate::keyed_pool::KeyedPool) and shares its [`IndexedPool`] methods.
+/// ```
+/// use value_pool::ordered_pool_map::OrderedPoolMap;
+///
+/// let mut scores: OrderedPoolMap<u32, &str> = OrderedPoolMap::new();
+/// let (carol, _) = scores.insert(30, "carol");
+/// scores.insert(10, "alice");
+/// scores.insert(20, "bob");
+///
+/// let names: Vec<&str> = scores.iter().map(|(_, _, name)| *name).collect();
+/// assert_eq!(names, vec!["alice", "bob", "carol"]);
+/// assert_eq!(scores.get_by_ref(carol), Some((&30, &"carol")));
+///
+/// let top: Vec<u32> = scores.range(15..).map(|(_, score, _)| *score).collect();
+/// assert_eq!(top, vec![20, 30]);
+/// ```
+pub type OrderedPoolMap<K, V> = IndexedPool<K, V, BTreeMap<K, ValueRef<(K, V)>>>;
+
+impl<K: Ord + Clone, V> OrderedPoolMap<K, V> {
+    /// Creates a new, empty [`OrderedPoolMap`].
+    #[inline]
+    pub const fn new() -> OrderedPoolMap<K, V> {
+        OrderedPoolMap {
+            pool: (ValuePool::new()),
+            index: (BTreeMap::new()),
+        }
+    }
+
+    /// Returns the position of the pair stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn ref_of<Q>(&self, key: &Q) -> Option<ValueRef<(K, V)>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.index.get(key).copied()
+    }
+
+    /// Returns true, if a pair is stored under `key`.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.index.contains_key(key)
+    }
+
+    /// Gets a borrow of the value stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.value_at(self.ref_of(key))
+    }
+
+    /// Gets a mut borrow of the value stored under `key`.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.value_at_mut(self.ref_of(key))
+    }
+
+    /// Removes the pair stored under `key` and returns its value.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let reference = self.index.remove(key);
+        self.take_value(reference)
+    }
+
+    /// Returns the position of the pair with the smallest key.
+    #[inline]
+    pub fn first(&self) -> Option<ValueRef<(K, V)>> {
+        self.index.values().next().copied()
+    }
+
+    /// Returns the position of the pair with the greatest key.
+    #[inline]
+    pub fn last(&self) -> Option<ValueRef<(K, V)>> {
+        self.index.values().next_back().copied()
+    }
+
+    #[inline]
+    fn pair(&self, reference: ValueRef<(K, V)>) -> (ValueRef<(K, V)>, &K, &V) {
+        let (key, value) = self
+            .pool
+            .get(reference)
+            .expect("Indexed pairs to be stored");
+        (reference, key, value)
+    }
+
+    /// Iterates over all pairs in key order.
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (ValueRef<(K, V)>, &K, &V)> {
+        self.index.values().map(|reference| self.pair(*reference))
+    }
+
+    /// Iterates over all pairs whose key is within `range`, in key order.
+    ///
+    /// # Complexity
+    /// `O(log n + k)` where `k` is the number of returned pairs
+    #[inline]
+    pub fn range<Q, R>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (ValueRef<(K, V)>, &K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.index
+            .range(range)
+            .map(|(_, reference)| self.pair(*reference))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrderedPoolMap;
+
+    #[test]
+    fn test_order_and_refs_stay_in_sync() {
+        let mut map: OrderedPoolMap<String, u32> = OrderedPoolMap::new();
+        let (b_ref, _) = map.insert("b".to_string(), 2);
+        let (a_ref, _) = map.insert("a".to_string(), 1);
+        assert_eq!(map.insert("b".to_string(), 3), (b_ref, Some(2)));
+        assert_eq!((map.first(), map.last()), (Some(a_ref), Some(b_ref)));
+
+        assert_eq!(map.remove_by_ref(a_ref), Some(("a".to_string(), 1)));
+        assert!(!map.contains_key("a"));
+        let (c_ref, _) = map.insert("c".to_string(), 4);
+        assert_eq!(c_ref, a_ref);
+        let keys: Vec<&str> = map.iter().rev().map(|(_, key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["c", "b"]);
+        assert_eq!(map.remove("b"), Some(3));
+        assert_eq!(map.element_count(), 1);
+    }
+}
@@ -0,0 +1,13 @@
+//! This module groups the pool types built on [`ValuePool<T>`](crate::ValuePool).
+//! Every module stays available at its old path as well, e.g. [`crate::dirty_pool`].
+//! ```
+//! use value_pool::pool::dirty_pool::DirtyPool;
+//!
+//! let mut pool: DirtyPool<u32> = DirtyPool::new();
+//! let reference = pool.push(1);
+//! assert_eq!(pool.drain_dirty().collect::<Vec<_>>(), vec![reference]);
+//! ```
+pub use crate::{
+    dirty_pool, expiring_pool, keyed_pool, multi_size_pool, object_pool, observed_pool,
+    ordered_pool_map, sequential_pool, smart_value_pool, versioned_pool,
+};
@@ -0,0 +1,274 @@
+//! This module implements [`PoolSet<K, T>`], which manages several [`ValuePool<T>`]s by key and issues handles valid across all of them.
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    pool: ValuePool<T>,
+    generation: u64,
+}
+
+/// Identifies one pool of a [`PoolSet<K, T>`]. It stays unique even after the pool got dropped and its position reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PoolId {
+    index: usize,
+    generation: u64,
+}
+
+/// A handle to a value in one pool of a [`PoolSet<K, T>`]: the [`PoolId`] combined with a [`ValueRef<T>`].
+#[derive(Debug)]
+pub struct SetRef<T> {
+    pool: PoolId,
+    reference: ValueRef<T>,
+}
+
+impl<T> SetRef<T> {
+    /// Returns the pool of this handle.
+    #[inline]
+    pub fn pool_id(&self) -> PoolId {
+        self.pool
+    }
+
+    /// Returns the position of this handle inside its pool.
+    #[inline]
+    pub fn reference(&self) -> ValueRef<T> {
+        self.reference
+    }
+}
+
+impl<T> Clone for SetRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for SetRef<T> {}
+
+impl<T> PartialEq for SetRef<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.pool == other.pool && self.reference == other.reference
+    }
+}
+impl<T> Eq for SetRef<T> {}
+
+impl<T> Hash for SetRef<T> {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.pool.hash(state);
+        self.reference.hash(state);
+    }
+}
+
+/// A [`PoolSet<K, T>`] holds one [`ValuePool<T>`] per key `K`, e.g. one per level or world chunk.
+/// Values are addressed with [`SetRef<T>`]s, which remember their pool. Dropping a pool with [`PoolSet::drop_pool`]
+/// invalidates all of its handles at once, without touching them.
+/// ```
+/// use value_pool::pool_set::PoolSet;
+///
+/// let mut levels: PoolSet<&str, u32> = PoolSet::new();
+/// let forest = levels.create_pool("forest");
+/// let cave = levels.create_pool("cave");
+///
+/// let tree = levels.push(forest, 1).unwrap();
+/// let bat = levels.push(cave, 2).unwrap();
+/// assert_eq!(levels.get(tree), Some(&1));
+///
+/// let forest_values = levels.drop_pool("forest").unwrap();
+/// assert_eq!(forest_values.element_count(), 1);
+/// assert_eq!(levels.get(tree), None);
+///
+/// // a new pool under the same key doesn't make old handles valid again
+/// let new_forest = levels.create_pool("forest");
+/// levels.push(new_forest, 3);
+/// assert_eq!(levels.get(tree), None);
+/// assert_eq!(levels.get(bat), Some(&2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PoolSet<K, T> {
+    pools: ValuePool<Entry<T>>,
+    ids: HashMap<K, PoolId>,
+    next_generation: u64,
+}
+
+impl<K: Hash + Eq, T> Default for PoolSet<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, T> PoolSet<K, T> {
+    /// Creates a new, empty [`PoolSet`].
+    #[inline]
+    pub fn new() -> PoolSet<K, T> {
+        PoolSet {
+            pools: (ValuePool::new()),
+            ids: (HashMap::new()),
+            next_generation: 0,
+        }
+    }
+
+    /// Returns the number of pools.
+    #[inline]
+    pub fn pool_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns the number of values stored in all pools.
+    ///
+    /// # Complexity
+    /// `O(p)` where `p` is the number of pools
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.pools
+            .occupied()
+            .map(|(_, entry)| entry.pool.element_count())
+            .sum()
+    }
+
+    /// Creates an empty pool for `key` and returns its id. If `key` already has a pool, its id is returned.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    pub fn create_pool(&mut self, key: K) -> PoolId {
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+        let generation = self.next_generation;
+        self.next_generation += 1;
+        let reference = self.pools.push(Entry {
+            pool: (ValuePool::new()),
+            generation,
+        });
+        let id = PoolId {
+            index: (reference.index.get()),
+            generation,
+        };
+        self.ids.insert(key, id);
+        id
+    }
+
+    /// Returns the id of the pool for `key`.
+    #[inline]
+    pub fn pool_id<Q>(&self, key: &Q) -> Option<PoolId>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(key).copied()
+    }
+
+    #[inline]
+    fn entry(&self, id: PoolId) -> Option<&Entry<T>> {
+        self.pools
+            .get(ValueRef::new(id.index))
+            .filter(|entry| entry.generation == id.generation)
+    }
+
+    #[inline]
+    fn entry_mut(&mut self, id: PoolId) -> Option<&mut Entry<T>> {
+        self.pools
+            .get_mut(ValueRef::new(id.index))
+            .filter(|entry| entry.generation == id.generation)
+    }
+
+    /// Gives read-only access to the pool `id`.
+    #[inline]
+    pub fn pool(&self, id: PoolId) -> Option<&ValuePool<T>> {
+        Some(&self.entry(id)?.pool)
+    }
+
+    /// Gives mutable access to the pool `id`.
+    #[inline]
+    pub fn pool_mut(&mut self, id: PoolId) -> Option<&mut ValuePool<T>> {
+        Some(&mut self.entry_mut(id)?.pool)
+    }
+
+    /// Removes the pool of `key` and returns it. All [`SetRef<T>`]s into it become invalid.
+    /// The values are dropped together with the returned pool, so that cost can be moved elsewhere.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    pub fn drop_pool<Q>(&mut self, key: &Q) -> Option<ValuePool<T>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = self.ids.remove(key)?;
+        self.pools
+            .take(ValueRef::new(id.index))
+            .map(|entry| entry.pool)
+    }
+
+    /// Pushes `value` into the pool `id`. Returns [`None`] if the pool doesn't exist anymore.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push(&mut self, id: PoolId, value: T) -> Option<SetRef<T>> {
+        let reference = self.entry_mut(id)?.pool.push(value);
+        Some(SetRef {
+            pool: id,
+            reference,
+        })
+    }
+
+    /// Gets a borrow of the value at `reference`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get(&self, reference: SetRef<T>) -> Option<&T> {
+        self.entry(reference.pool)?.pool.get(reference.reference)
+    }
+
+    /// Gets a mut borrow of the value at `reference`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_mut(&mut self, reference: SetRef<T>) -> Option<&mut T> {
+        self.entry_mut(reference.pool)?
+            .pool
+            .get_mut(reference.reference)
+    }
+
+    /// Takes the value at `reference`, see [`ValuePool::take`].
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn take(&mut self, reference: SetRef<T>) -> Option<T> {
+        self.entry_mut(reference.pool)?
+            .pool
+            .take(reference.reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoolSet;
+
+    #[test]
+    fn test_reused_pool_position() {
+        let mut set: PoolSet<u32, &str> = PoolSet::new();
+        let first = set.create_pool(1);
+        assert_eq!(set.create_pool(1), first);
+        let value = set.push(first, "a").unwrap();
+
+        assert!(set.drop_pool(&1).is_some());
+        assert!(set.drop_pool(&1).is_none());
+        let second = set.create_pool(2);
+        // `second` reuses the position of `first`
+        assert_ne!(first, second);
+        assert_eq!(set.push(first, "b"), None);
+        assert_eq!(set.take(value), None);
+        assert!(set.pool(first).is_none());
+
+        let other = set.push(second, "c").unwrap();
+        assert_eq!(set.get_mut(other).map(|v| *v), Some("c"));
+        assert_eq!(set.pool_id(&2), Some(second));
+        assert_eq!((set.pool_count(), set.element_count()), (1, 1));
+    }
+}
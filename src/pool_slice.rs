@@ -0,0 +1,148 @@
+//! This module implements [`PoolSlice<'a, T>`], a read-only view of some of the values of a [`ValuePool<T>`].
+use crate::{ValuePool, ValueRef};
+
+#[derive(Debug)]
+enum Selection<'a, T> {
+    Refs(&'a [ValueRef<T>]),
+    Filter(fn(&T) -> bool),
+}
+
+impl<T> Clone for Selection<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Selection<'_, T> {}
+
+/// A [`PoolSlice<'a, T>`] borrows a [`ValuePool<T>`] but only exposes a subset of its values,
+/// selected by an explicit list of [`ValueRef<T>`]s or by a predicate. It never copies values and implements [`Copy`].
+/// ```
+/// use value_pool::{ValuePool, pool_slice::PoolSlice};
+///
+/// let mut pool: ValuePool<i32> = ValuePool::new();
+/// let minus_one = pool.push(-1);
+/// let two = pool.push(2);
+/// let three = pool.push(3);
+///
+/// let positive = PoolSlice::filter(&pool, |v| *v > 0);
+/// assert_eq!(positive.get(minus_one), None);
+/// assert_eq!(positive.iter().map(|(_, v)| *v).sum::<i32>(), 5);
+///
+/// let selected = [three, minus_one];
+/// let listed = PoolSlice::from_refs(&pool, &selected);
+/// assert_eq!(listed.get(two), None);
+/// assert_eq!(listed.iter().map(|(r, _)| r).collect::<Vec<_>>(), vec![three, minus_one]);
+/// ```
+#[derive(Debug)]
+pub struct PoolSlice<'a, T> {
+    pool: &'a ValuePool<T>,
+    selection: Selection<'a, T>,
+}
+
+impl<T> Clone for PoolSlice<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for PoolSlice<'_, T> {}
+
+impl<'a, T> PoolSlice<'a, T> {
+    /// Creates a [`PoolSlice`] that contains the values at `refs`. Positions without a value are skipped.
+    #[inline]
+    pub fn from_refs(pool: &'a ValuePool<T>, refs: &'a [ValueRef<T>]) -> PoolSlice<'a, T> {
+        PoolSlice {
+            pool,
+            selection: (Selection::Refs(refs)),
+        }
+    }
+
+    /// Creates a [`PoolSlice`] that contains all values for which `predicate` returns true.
+    #[inline]
+    pub fn filter(pool: &'a ValuePool<T>, predicate: fn(&T) -> bool) -> PoolSlice<'a, T> {
+        PoolSlice {
+            pool,
+            selection: (Selection::Filter(predicate)),
+        }
+    }
+
+    /// Returns true if a value is stored at `reference` and it's part of this [`PoolSlice`].
+    ///
+    /// # Complexity
+    /// `O(1)` for a predicate, `O(n)` for a list of `n` refs
+    #[inline]
+    pub fn contains(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        self.get(reference).is_some()
+    }
+
+    /// Gets a borrow of the value at `reference` if it's part of this [`PoolSlice`].
+    ///
+    /// # Complexity
+    /// `O(1)` for a predicate, `O(n)` for a list of `n` refs
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&'a T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.get(reference)?;
+        let included = match self.selection {
+            Selection::Refs(refs) => refs.contains(&reference),
+            Selection::Filter(predicate) => predicate(value),
+        };
+        included.then_some(value)
+    }
+
+    /// Returns the number of values in this [`PoolSlice`].
+    ///
+    /// # Complexity
+    /// `O(n)`
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns true if this [`PoolSlice`] contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Iterates over all values of this [`PoolSlice`]. A list of refs is iterated in its order
+    /// (refs listed more than once are returned more than once), a predicate in slot order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ValueRef<T>, &'a T)> + 'a {
+        let pool = self.pool;
+        let (refs, predicate) = match self.selection {
+            Selection::Refs(refs) => (refs, None),
+            Selection::Filter(predicate) => (&[][..], Some(predicate)),
+        };
+        refs.iter()
+            .filter_map(move |reference| Some((*reference, pool.get(*reference)?)))
+            .chain(
+                predicate
+                    .into_iter()
+                    .flat_map(move |predicate| pool.occupied().filter(move |(_, v)| predicate(v))),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoolSlice;
+    use crate::ValuePool;
+
+    #[test]
+    fn test_removed_values_are_skipped() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        let refs: Vec<_> = (0..4).map(|i| pool.push(i)).collect();
+        pool.remove(refs[1]);
+        pool.remove(refs[2]);
+
+        let listed = PoolSlice::from_refs(&pool, &refs[..3]);
+        assert_eq!(listed.len(), 1);
+        assert!(!listed.contains(refs[1]));
+        assert!(!listed.contains(refs[3]));
+
+        let even = PoolSlice::filter(&pool, |v| v % 2 == 0);
+        assert_eq!(even.iter().collect::<Vec<_>>(), vec![(refs[0], &0)]);
+        assert!(PoolSlice::filter(&pool, |v| *v > 10).is_empty());
+    }
+}
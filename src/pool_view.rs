@@ -0,0 +1,148 @@
+//! This module implements [`PoolViewMut<T>`] and [`ValuePool::split_by`], which splits a pool into two disjoint mutable views.
+use crate::{ValuePool, ValueRef};
+
+/// Mutable access to a part of the items of a [`ValuePool<T>`], created by [`ValuePool::split_by`].
+/// The views of one split never share an item, so they can be moved to different threads, e.g. with [`std::thread::scope`].
+#[derive(Debug)]
+pub struct PoolViewMut<'a, T> {
+    // ordered by position
+    items: Vec<(ValueRef<T>, &'a mut T)>,
+}
+
+impl<'a, T> PoolViewMut<'a, T> {
+    /// Returns the number of items in this view.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if this view contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    #[inline]
+    fn position(&self, reference: impl Into<ValueRef<T>>) -> Option<usize> {
+        let reference: ValueRef<T> = reference.into();
+        self.items
+            .binary_search_by(|(item, _)| item.cmp(&reference))
+            .ok()
+    }
+
+    /// Returns true if the item at `reference` belongs to this view.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn contains(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        self.position(reference).is_some()
+    }
+
+    /// Gets a borrow of the item at `reference`, if it belongs to this view.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        let position = self.position(reference)?;
+        Some(&*self.items[position].1)
+    }
+
+    /// Gets a mut borrow of the item at `reference`, if it belongs to this view.
+    ///
+    /// # Complexity
+    /// `O(log n)`
+    #[inline]
+    pub fn get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Option<&mut T> {
+        let position = self.position(reference)?;
+        Some(&mut *self.items[position].1)
+    }
+
+    /// Iterates over the items of this view in slot order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ValueRef<T>, &T)> {
+        self.items
+            .iter()
+            .map(|(reference, value)| (*reference, &**value))
+    }
+
+    /// Iterates mutably over the items of this view in slot order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ValueRef<T>, &mut T)> + use<'_, 'a, T> {
+        self.items
+            .iter_mut()
+            .map(|(reference, value)| (*reference, &mut **value))
+    }
+
+    /// Turns this view into an iterator over its items, keeping the borrow of the pool.
+    #[inline]
+    pub fn into_iter_mut(self) -> impl Iterator<Item = (ValueRef<T>, &'a mut T)> {
+        self.items.into_iter()
+    }
+}
+
+impl<T> ValuePool<T> {
+    /// Splits the items of this [`ValuePool`] into two disjoint mutable views: items for which `predicate` returns true,
+    /// and all others. `predicate` is called once per item. This allows two subsystems to mutate their part at the same time.
+    /// ```
+    /// use value_pool::ValuePool;
+    ///
+    /// let mut pool: ValuePool<i32> = ValuePool::new();
+    /// let refs: Vec<_> = [-2, 5, -7, 1].into_iter().map(|value| pool.push(value)).collect();
+    ///
+    /// let (mut negative, mut positive) = pool.split_by(|value| *value < 0);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| negative.iter_mut().for_each(|(_, value)| *value = -*value));
+    ///     scope.spawn(|| positive.iter_mut().for_each(|(_, value)| *value *= 10));
+    /// });
+    /// assert_eq!(pool.get(refs[0]), Some(&2));
+    /// assert_eq!(pool.get(refs[1]), Some(&50));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    pub fn split_by(
+        &mut self,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> (PoolViewMut<'_, T>, PoolViewMut<'_, T>) {
+        let mut matching = Vec::new();
+        let mut rest = Vec::new();
+        for (index, slot) in self.store.iter_mut().enumerate() {
+            if let Some(value) = slot {
+                let item = (ValueRef::new(index), value);
+                if predicate(item.1) {
+                    matching.push(item);
+                } else {
+                    rest.push(item);
+                }
+            }
+        }
+        (PoolViewMut { items: matching }, PoolViewMut { items: rest })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_views_are_disjoint() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        for value in 0..6 {
+            pool.push(value);
+        }
+        pool.take(ValueRef::new(2));
+        let (mut even, odd) = pool.split_by(|value| value % 2 == 0);
+        assert_eq!((even.len(), odd.len()), (2, 3));
+        assert!(!even.contains(ValueRef::new(2)));
+        assert!(odd.contains(ValueRef::new(3)));
+        assert_eq!(even.get_mut(ValueRef::new(1)), None);
+        *even.get_mut(ValueRef::new(4)).unwrap() = 40;
+        let refs: Vec<usize> = odd.iter().map(|(r, _)| r.index.get()).collect();
+        assert_eq!(refs, vec![1, 3, 5]);
+        drop(odd);
+        assert_eq!(even.into_iter_mut().count(), 2);
+        assert_eq!(pool.get(ValueRef::new(4)), Some(&40));
+    }
+}
@@ -0,0 +1,18 @@
+//! This module re-exports the most commonly used types of this crate, so `use value_pool::prelude::*;` is enough to get started.
+//! ```
+//! use value_pool::prelude::*;
+//!
+//! let mut pool: ValuePool<&str> = ValuePool::new();
+//! let reference: ValueRef<&str> = pool.push("hello");
+//! let untyped: UntypedValueRef = reference.into();
+//! assert_eq!(pool.get(untyped), Some(&"hello"));
+//! assert_eq!(pool.try_take(untyped), Ok("hello"));
+//! assert_eq!(pool.try_take(untyped), Err(SlotError::Empty));
+//! ```
+pub use crate::{
+    handle_container::HandleContainer,
+    multi_list::{ListId, MultiList},
+    ref_set::RefSet,
+    smart_value_pool::{PoisonError, SmartValuePool},
+    GrowthStrategy, SlotError, UntypedValueRef, ValuePool, ValueRef,
+};
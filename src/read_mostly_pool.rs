@@ -0,0 +1,465 @@
+//! This module implements [`ReadMostlyPool<T>`], a concurrent pool optimized for workloads with many more reads than writes.
+//! Readers never take a lock, writers are serialized by a [`Mutex`] and use epoch based reclamation to free removed values.
+use std::{marker::PhantomData, ptr::null_mut};
+
+use nonmax::NonMaxUsize;
+
+use crate::{
+    sync::{concurrency_check, load_mut, AtomicPtr, AtomicUsize, Mutex, MutexGuard, Ordering},
+    ValueRef,
+};
+
+// the first segment holds `1 << BASE_SHIFT` slots, every following segment twice as many as the one before
+const BASE_SHIFT: u32 = 5;
+const SEGMENTS: usize = (usize::BITS - BASE_SHIFT) as usize;
+
+/// Returns the segment and the offset within that segment of `index`.
+/// The last `1 << BASE_SHIFT` indices map to the non-existent segment `SEGMENTS`.
+#[inline]
+fn locate(index: usize) -> (usize, usize) {
+    let scaled = (index >> BASE_SHIFT) + 1;
+    let segment = (usize::BITS - 1 - scaled.leading_zeros()) as usize;
+    let start = ((1usize << segment) - 1) << BASE_SHIFT;
+    (segment, index - start)
+}
+
+#[inline]
+fn segment_len(segment: usize) -> usize {
+    1 << (segment as u32 + BASE_SHIFT)
+}
+
+/// A removed value that gets dropped together with this struct.
+/// It's kept as a raw pointer, since readers could still borrow it.
+#[derive(Debug)]
+struct Retired<T> {
+    epoch: usize,
+    value: *mut T,
+}
+
+// SAFETY: `Retired<T>` owns the `T` it points to.
+unsafe impl<T: Send> Send for Retired<T> {}
+
+impl<T> Drop for Retired<T> {
+    fn drop(&mut self) {
+        // SAFETY: `value` was created by `Box::into_raw` and is only dropped once all readers that could see it are gone.
+        drop(unsafe { Box::from_raw(self.value) });
+    }
+}
+
+#[derive(Debug)]
+struct Writer<T> {
+    // number of slots ever handed out
+    len: usize,
+    open_indices: Vec<NonMaxUsize>,
+    // removed values, ordered by the epoch they got removed in
+    garbage: Vec<Retired<T>>,
+}
+
+/// A [`ReadMostlyPool<T>`] can be shared between threads (e.g. with an [`Arc`](std::sync::Arc)).
+/// All methods take `&self`.
+///
+/// Reading happens trough a [`ReadGuard`] returned by [`ReadMostlyPool::pin`], which is lock-free.
+/// Writing ([`push`](ReadMostlyPool::push), [`remove`](ReadMostlyPool::remove), [`replace`](ReadMostlyPool::replace))
+/// takes an internal lock. Removed values are freed as soon as no [`ReadGuard`] that could have seen them exists anymore.
+/// Long living [`ReadGuard`]s only delay freeing values, they never block writers.
+///
+/// Like with [`ValuePool<T>`](crate::ValuePool), a removed position can be reused by a later push.
+/// ```
+/// use std::sync::Arc;
+/// use value_pool::read_mostly_pool::ReadMostlyPool;
+///
+/// let pool: Arc<ReadMostlyPool<String>> = Arc::new(ReadMostlyPool::new());
+/// let config = pool.push("fast".to_string());
+///
+/// let readers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let pool = Arc::clone(&pool);
+///         std::thread::spawn(move || {
+///             let guard = pool.pin();
+///             assert!(guard.get(config).is_some_and(|mode| mode == "fast" || mode == "safe"));
+///         })
+///     })
+///     .collect();
+/// pool.replace(config, "safe".to_string()).unwrap();
+/// for reader in readers {
+///     reader.join().unwrap();
+/// }
+/// assert_eq!(pool.pin().get(config).map(String::as_str), Some("safe"));
+/// ```
+#[derive(Debug)]
+pub struct ReadMostlyPool<T> {
+    // every segment is a leaked `Box<[AtomicPtr<T>]>` of length `segment_len(segment)`
+    segments: [AtomicPtr<AtomicPtr<T>>; SEGMENTS],
+    epoch: AtomicUsize,
+    // number of pinned readers, by parity of the epoch they pinned in
+    readers: [AtomicUsize; 2],
+    writer: Mutex<Writer<T>>,
+    // makes `ReadMostlyPool<T>` `Sync` only if `T` is `Sync` (and `Send`, trough `writer`)
+    type_info: PhantomData<Box<T>>,
+}
+
+impl<T> Default for ReadMostlyPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ReadMostlyPool<T> {
+    /// Creates a new, empty [`ReadMostlyPool`].
+    #[inline]
+    pub fn new() -> ReadMostlyPool<T> {
+        ReadMostlyPool {
+            segments: (std::array::from_fn(|_| AtomicPtr::new(null_mut()))),
+            epoch: (AtomicUsize::new(0)),
+            readers: ([AtomicUsize::new(0), AtomicUsize::new(0)]),
+            writer: (Mutex::new(Writer {
+                len: 0,
+                open_indices: (Vec::new()),
+                garbage: (Vec::new()),
+            })),
+            type_info: (PhantomData),
+        }
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Writer<T>> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the slot of `index` if its segment is allocated.
+    #[inline]
+    fn slot(&self, index: usize) -> Option<&AtomicPtr<T>> {
+        let (segment, offset) = locate(index);
+        let start = self.segments.get(segment)?.load(Ordering::Acquire);
+        if start.is_null() {
+            return None;
+        }
+        // SAFETY: `start` points to a live segment of length `segment_len(segment)` > `offset`.
+        // Segments are only freed in `drop`.
+        Some(unsafe { &*start.add(offset) })
+    }
+
+    /// Returns the number of values stored.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        let writer = self.lock();
+        writer.len - writer.open_indices.len()
+    }
+
+    /// Returns true if no values are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.element_count() == 0
+    }
+
+    /// Returns the number of removed values that still wait for readers to unpin before they are freed.
+    #[inline]
+    pub fn pending_garbage(&self) -> usize {
+        self.lock().garbage.len()
+    }
+
+    /// Returns a [`ReadGuard`] that allows lock-free reads. Values read trough it stay alive until it is dropped.
+    #[inline]
+    pub fn pin(&self) -> ReadGuard<'_, T> {
+        loop {
+            let epoch = self.epoch.load(Ordering::SeqCst);
+            let parity = epoch & 1;
+            self.readers[parity].fetch_add(1, Ordering::SeqCst);
+            // if the epoch moved on in the meantime, our count could be too late to protect anything
+            if self.epoch.load(Ordering::SeqCst) == epoch {
+                return ReadGuard { pool: self, parity };
+            }
+            self.readers[parity].fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Calls `f` with the value at `reference`, see [`ReadGuard::get`].
+    #[inline]
+    pub fn read<R>(&self, reference: impl Into<ValueRef<T>>, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.pin().get(reference).map(f)
+    }
+
+    /// Advances the epoch if possible and frees all garbage no reader can see anymore.
+    fn collect(&self, writer: &mut Writer<T>) {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        // readers only exist in the current and the previous epoch, the previous one shares the parity of the next
+        let epoch = if self.readers[(epoch + 1) & 1].load(Ordering::SeqCst) == 0 {
+            self.epoch.store(epoch + 1, Ordering::SeqCst);
+            epoch + 1
+        } else {
+            epoch
+        };
+        // garbage of epoch `e` could be seen by readers of `e` and `e - 1`, which are gone once the epoch is `e + 2`
+        let freeable = writer
+            .garbage
+            .iter()
+            .take_while(|retired| retired.epoch + 2 <= epoch)
+            .count();
+        writer.garbage.drain(..freeable);
+        concurrency_check!(
+            writer.garbage.windows(2).all(|w| w[0].epoch <= w[1].epoch),
+            "Garbage to be ordered by epoch"
+        );
+    }
+
+    /// `value` has to be unlinked from its slot, so no new reader can reach it.
+    #[inline]
+    fn retire(&self, writer: &mut Writer<T>, value: *mut T) {
+        concurrency_check!(!value.is_null(), "Retired value to be non-null");
+        writer.garbage.push(Retired {
+            epoch: (self.epoch.load(Ordering::SeqCst)),
+            value,
+        });
+    }
+
+    /// Pushes `value` and returns its position.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    pub fn push(&self, value: T) -> ValueRef<T> {
+        let mut writer = self.lock();
+        let index = match writer.open_indices.pop() {
+            Some(index) => index.get(),
+            None => {
+                writer.len += 1;
+                writer.len - 1
+            }
+        };
+        let (segment, _) = locate(index);
+        if self.segments[segment].load(Ordering::Acquire).is_null() {
+            let slots: Box<[AtomicPtr<T>]> = (0..segment_len(segment))
+                .map(|_| AtomicPtr::new(null_mut()))
+                .collect();
+            let start = Box::into_raw(slots) as *mut AtomicPtr<T>;
+            self.segments[segment].store(start, Ordering::Release);
+        }
+        let slot = self.slot(index).expect("Segment to be allocated");
+        let old = slot.swap(Box::into_raw(Box::new(value)), Ordering::AcqRel);
+        concurrency_check!(old.is_null(), "Pushed slot {index} to be empty");
+        self.collect(&mut writer);
+        ValueRef::new(index)
+    }
+
+    /// Removes the value at `reference`. Returns false if no value is stored there.
+    /// The value is dropped once no [`ReadGuard`] can see it anymore.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    pub fn remove(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        let mut writer = self.lock();
+        let Some(slot) = self.slot(reference.index.get()) else {
+            return false;
+        };
+        let value = slot.swap(null_mut(), Ordering::AcqRel);
+        if value.is_null() {
+            return false;
+        }
+        writer.open_indices.push(reference.index);
+        self.retire(&mut writer, value);
+        self.collect(&mut writer);
+        true
+    }
+
+    /// Replaces the value at `reference` with `value`. Returns `Err(value)` if no value is stored there.
+    /// The old value is dropped once no [`ReadGuard`] can see it anymore.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    pub fn replace(&self, reference: impl Into<ValueRef<T>>, value: T) -> Result<(), T> {
+        let reference: ValueRef<T> = reference.into();
+        let mut writer = self.lock();
+        let Some(slot) = self.slot(reference.index.get()) else {
+            return Err(value);
+        };
+        if slot.load(Ordering::Acquire).is_null() {
+            return Err(value);
+        }
+        let old = slot.swap(Box::into_raw(Box::new(value)), Ordering::AcqRel);
+        self.retire(&mut writer, old);
+        self.collect(&mut writer);
+        Ok(())
+    }
+
+    /// Takes the value at `reference`. This needs exclusive access, so no [`ReadGuard`] can exist.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self
+            .slot(reference.index.get())?
+            .swap(null_mut(), Ordering::AcqRel);
+        if value.is_null() {
+            return None;
+        }
+        self.lock().open_indices.push(reference.index);
+        // SAFETY: `value` was created by `Box::into_raw` and got unlinked. `&mut self` guarantees that no reader exists.
+        Some(*unsafe { Box::from_raw(value) })
+    }
+}
+
+impl<T> Drop for ReadMostlyPool<T> {
+    fn drop(&mut self) {
+        for (segment, start) in self.segments.iter_mut().enumerate() {
+            let start = load_mut(start);
+            if start.is_null() {
+                continue;
+            }
+            let slots = std::ptr::slice_from_raw_parts_mut(start, segment_len(segment));
+            // SAFETY: segments are created from a `Box<[AtomicPtr<T>]>` of this length in `push`.
+            let mut slots = unsafe { Box::from_raw(slots) };
+            for slot in slots.iter_mut() {
+                let value = load_mut(slot);
+                if !value.is_null() {
+                    // SAFETY: values are created by `Box::into_raw`, `&mut self` guarantees that no reader exists.
+                    drop(unsafe { Box::from_raw(value) });
+                }
+            }
+        }
+    }
+}
+
+/// Allows lock-free reads of a [`ReadMostlyPool<T>`]. Returned by [`ReadMostlyPool::pin`].
+///
+/// While it exists, no value it could have seen gets freed. Drop it soon to keep memory usage low.
+#[derive(Debug)]
+pub struct ReadGuard<'a, T> {
+    pool: &'a ReadMostlyPool<T>,
+    parity: usize,
+}
+
+impl<T> ReadGuard<'_, T> {
+    /// Gets a borrow of the value at `reference`. It stays valid as long as this guard, even if the value gets removed in the meantime.
+    ///
+    /// # Complexity
+    /// `O(1)`, lock-free
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self
+            .pool
+            .slot(reference.index.get())?
+            .load(Ordering::Acquire);
+        // SAFETY: non-null values are valid boxes. This guard is pinned, so `collect` doesn't free `value` until it is dropped.
+        unsafe { value.as_ref() }
+    }
+}
+
+impl<T> Drop for ReadGuard<'_, T> {
+    fn drop(&mut self) {
+        let pinned = self.pool.readers[self.parity].fetch_sub(1, Ordering::SeqCst);
+        concurrency_check!(pinned > 0, "Reader count to not underflow");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{locate, segment_len, ReadMostlyPool};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn test_locate() {
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(31), (0, 31));
+        assert_eq!(locate(32), (1, 0));
+        assert_eq!(locate(95), (1, 63));
+        assert_eq!(locate(96), (2, 0));
+        let last = usize::MAX - (1 << super::BASE_SHIFT);
+        let (segment, offset) = locate(last);
+        assert_eq!(
+            (segment, offset + 1),
+            (super::SEGMENTS - 1, segment_len(segment))
+        );
+        assert_eq!(locate(last + 1), (super::SEGMENTS, 0));
+    }
+
+    #[test]
+    fn test_garbage_waits_for_readers() {
+        struct Counted(Arc<AtomicUsize>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let drops = Arc::new(AtomicUsize::new(0));
+        let mut pool = ReadMostlyPool::new();
+        let first = pool.push(Counted(Arc::clone(&drops)));
+
+        let guard = pool.pin();
+        let value = guard.get(first).unwrap();
+        assert!(pool.remove(first));
+        for _ in 0..4 {
+            let other = pool.push(Counted(Arc::clone(&drops)));
+            pool.remove(other);
+        }
+        // `value` must still be alive
+        assert_eq!(Arc::strong_count(&value.0), 6);
+        assert!(drops.load(Ordering::SeqCst) <= 3);
+        drop(guard);
+
+        let other = pool.push(Counted(Arc::clone(&drops)));
+        pool.push(Counted(Arc::clone(&drops)));
+        assert!(pool.take(other).is_some());
+        assert_eq!(pool.pending_garbage(), 0);
+        assert_eq!(drops.load(Ordering::SeqCst), 6);
+        drop(pool);
+        assert_eq!(drops.load(Ordering::SeqCst), 7);
+    }
+
+    #[test]
+    fn test_concurrent_reads_and_writes() {
+        let pool: Arc<ReadMostlyPool<Vec<usize>>> = Arc::new(ReadMostlyPool::new());
+        let references: Vec<_> = (0..64).map(|i| pool.push(vec![i; 8])).collect();
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        let guard = pool.pin();
+                        for reference in references.iter() {
+                            if let Some(values) = guard.get(*reference) {
+                                assert!(values.iter().all(|v| *v == values[0]));
+                            }
+                        }
+                    }
+                });
+            }
+            s.spawn(|| {
+                for round in 0..200 {
+                    let reference = references[round % references.len()];
+                    if pool.remove(reference) {
+                        pool.push(vec![round; 8]);
+                    }
+                }
+            });
+        });
+        assert_eq!(pool.element_count(), 64);
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::ReadMostlyPool;
+    use loom::{sync::Arc, thread};
+
+    // only pushes and reads: reclaiming removed values relies on the total order of `SeqCst`, which loom doesn't model
+    #[test]
+    fn test_segment_publish_and_retire() {
+        loom::model(|| {
+            let pool = Arc::new(ReadMostlyPool::new());
+            let reader = {
+                let pool = Arc::clone(&pool);
+                // the first push allocates and publishes the segment
+                thread::spawn(move || pool.read(crate::ValueRef::new(0), |value| *value))
+            };
+            let reference = pool.push(1usize);
+            assert!(matches!(reader.join().unwrap(), None | Some(1)));
+            assert_eq!(pool.read(reference, |value| *value), Some(1));
+            // the last `Arc` frees the segment and the value
+        });
+    }
+}
@@ -0,0 +1,240 @@
+//! This module implements [`RefBiMap<A, B>`] which links [`ValueRef<A>`]s with [`ValueRef<B>`]s in both directions.
+use crate::{ValuePool, ValueRef};
+
+/// [`RefBiMap<A, B>`] maps [`ValueRef<A>`] to [`ValueRef<B>`] and the other way round.
+/// Useful if two [`ValuePool`]s are linked, for example entities and their render proxies.
+///
+/// Every [`ValueRef<A>`] is linked with at most one [`ValueRef<B>`] and vice versa.
+/// Both directions are stored as vectors indexed by the position of the ref, so lookups are `O(1)`.
+/// ```
+/// use value_pool::{ValuePool, ref_bi_map::RefBiMap};
+///
+/// let mut entities: ValuePool<&str> = ValuePool::new();
+/// let mut proxies: ValuePool<u32> = ValuePool::new();
+/// let mut links: RefBiMap<&str, u32> = RefBiMap::new();
+///
+/// let player = entities.push("player");
+/// let player_proxy = proxies.push(7);
+/// links.insert(player, player_proxy);
+///
+/// assert_eq!(links.get_right(player), Some(player_proxy));
+/// assert_eq!(links.get_left(player_proxy), Some(player));
+///
+/// // The player gets removed, its proxy has to go as well
+/// entities.remove(player);
+/// assert_eq!(links.remove_left_cascade(player, &mut proxies), Some(7));
+/// assert!(proxies.is_empty());
+/// assert!(links.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RefBiMap<A, B> {
+    left_to_right: Vec<Option<ValueRef<B>>>,
+    right_to_left: Vec<Option<ValueRef<A>>>,
+    len: usize,
+}
+
+impl<A, B> Default for RefBiMap<A, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn set_slot<T>(slots: &mut Vec<Option<T>>, index: usize, value: Option<T>) -> Option<T> {
+    if index >= slots.len() {
+        // nothing to unset
+        value.as_ref()?;
+        slots.resize_with(index + 1, || None);
+    }
+    std::mem::replace(&mut slots[index], value)
+}
+
+impl<A, B> RefBiMap<A, B> {
+    /// Creates a new, empty [`RefBiMap`]. This doesn't allocate and can be used in `static`s.
+    #[inline]
+    pub const fn new() -> RefBiMap<A, B> {
+        RefBiMap {
+            left_to_right: (Vec::new()),
+            right_to_left: (Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of links.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no links.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Links `left` with `right`. Existing links of `left` or `right` are removed first.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn insert(&mut self, left: impl Into<ValueRef<A>>, right: impl Into<ValueRef<B>>) {
+        let (left, right) = (left.into(), right.into());
+        self.remove_left(left);
+        self.remove_right(right);
+        set_slot(&mut self.left_to_right, left.index.get(), Some(right));
+        set_slot(&mut self.right_to_left, right.index.get(), Some(left));
+        self.len += 1;
+    }
+
+    /// Returns the [`ValueRef<B>`] linked with `left`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_right(&self, left: impl Into<ValueRef<A>>) -> Option<ValueRef<B>> {
+        let left: ValueRef<A> = left.into();
+        self.left_to_right.get(left.index.get()).copied().flatten()
+    }
+
+    /// Returns the [`ValueRef<A>`] linked with `right`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_left(&self, right: impl Into<ValueRef<B>>) -> Option<ValueRef<A>> {
+        let right: ValueRef<B> = right.into();
+        self.right_to_left.get(right.index.get()).copied().flatten()
+    }
+
+    /// Removes the link of `left` and returns the [`ValueRef<B>`] it was linked with.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove_left(&mut self, left: impl Into<ValueRef<A>>) -> Option<ValueRef<B>> {
+        let left: ValueRef<A> = left.into();
+        let right = set_slot(&mut self.left_to_right, left.index.get(), None)?;
+        set_slot(&mut self.right_to_left, right.index.get(), None);
+        self.len -= 1;
+        Some(right)
+    }
+
+    /// Removes the link of `right` and returns the [`ValueRef<A>`] it was linked with.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove_right(&mut self, right: impl Into<ValueRef<B>>) -> Option<ValueRef<A>> {
+        let right: ValueRef<B> = right.into();
+        let left = set_slot(&mut self.right_to_left, right.index.get(), None)?;
+        set_slot(&mut self.left_to_right, left.index.get(), None);
+        self.len -= 1;
+        Some(left)
+    }
+
+    /// Removes the link of `left` and takes the linked value out of `right_pool`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove_left_cascade(
+        &mut self,
+        left: impl Into<ValueRef<A>>,
+        right_pool: &mut ValuePool<B>,
+    ) -> Option<B> {
+        let right = self.remove_left(left)?;
+        right_pool.take(right)
+    }
+
+    /// Removes the link of `right` and takes the linked value out of `left_pool`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove_right_cascade(
+        &mut self,
+        right: impl Into<ValueRef<B>>,
+        left_pool: &mut ValuePool<A>,
+    ) -> Option<A> {
+        let left = self.remove_right(right)?;
+        left_pool.take(left)
+    }
+
+    /// Exchanges the links of `left_1` and `left_2`. Useful after swapping both in their [`ValuePool`],
+    /// see [`ValuePool::swap_tracked`].
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn swap_left(&mut self, left_1: impl Into<ValueRef<A>>, left_2: impl Into<ValueRef<A>>) {
+        let (left_1, left_2) = (left_1.into(), left_2.into());
+        let right_1 = self.remove_left(left_1);
+        let right_2 = self.remove_left(left_2);
+        if let Some(right_1) = right_1 {
+            self.insert(left_2, right_1);
+        }
+        if let Some(right_2) = right_2 {
+            self.insert(left_1, right_2);
+        }
+    }
+
+    /// Exchanges the links of `right_1` and `right_2`. Useful after swapping both in their [`ValuePool`],
+    /// see [`ValuePool::swap_tracked`].
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn swap_right(&mut self, right_1: impl Into<ValueRef<B>>, right_2: impl Into<ValueRef<B>>) {
+        let (right_1, right_2) = (right_1.into(), right_2.into());
+        let left_1 = self.remove_right(right_1);
+        let left_2 = self.remove_right(right_2);
+        if let Some(left_1) = left_1 {
+            self.insert(left_1, right_2);
+        }
+        if let Some(left_2) = left_2 {
+            self.insert(left_2, right_1);
+        }
+    }
+
+    /// Iterates over all links, ordered by the position of the [`ValueRef<A>`].
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ValueRef<A>, ValueRef<B>)> + '_ {
+        self.left_to_right
+            .iter()
+            .enumerate()
+            .filter_map(|(index, right)| Some((ValueRef::new(index), (*right)?)))
+    }
+
+    /// Removes all links.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.left_to_right.clear();
+        self.right_to_left.clear();
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RefBiMap;
+    use crate::ValueRef;
+
+    #[test]
+    fn test_relinking_removes_old_links() {
+        let mut links: RefBiMap<u8, u16> = RefBiMap::new();
+        links.insert(ValueRef::new(0), ValueRef::new(5));
+        links.insert(ValueRef::new(1), ValueRef::new(5));
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links.get_right(ValueRef::new(0)), None);
+        assert_eq!(links.get_left(ValueRef::new(5)), Some(ValueRef::new(1)));
+
+        links.insert(ValueRef::new(1), ValueRef::new(2));
+        assert_eq!(links.len(), 1);
+        assert_eq!(links.get_left(ValueRef::new(5)), None);
+        assert_eq!(
+            links.iter().collect::<Vec<_>>(),
+            vec![(ValueRef::new(1), ValueRef::new(2))]
+        );
+    }
+}
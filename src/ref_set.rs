@@ -0,0 +1,298 @@
+//! This module implements [`RefSet<T>`], a set of [`ValueRef<T>`]s backed by a bitset.
+use std::marker::PhantomData;
+
+use crate::ValueRef;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A [`RefSet<T>`] stores a set of [`ValueRef<T>`]s as one bit per position, which makes it a compact and fast
+/// replacement for a `HashSet<ValueRef<T>>` as long as the positions are dense (which they are in a [`ValuePool<T>`](crate::ValuePool)).
+/// Its memory usage grows with the greatest stored position.
+/// ```
+/// use value_pool::{ValuePool, ref_set::RefSet};
+///
+/// let mut pool: ValuePool<&str> = ValuePool::new();
+/// let a = pool.push("a");
+/// let b = pool.push("b");
+/// let c = pool.push("c");
+///
+/// let selected: RefSet<&str> = [a, b].into_iter().collect();
+/// let mut visible = RefSet::new();
+/// visible.insert(b);
+/// visible.insert(c);
+///
+/// let both = selected.intersection(&visible);
+/// assert_eq!(both.iter().collect::<Vec<_>>(), vec![b]);
+/// assert_eq!(selected.union(&visible).len(), 3);
+/// assert!(selected.difference(&visible).contains(a));
+/// ```
+#[derive(Debug)]
+pub struct RefSet<T> {
+    words: Vec<u64>,
+    len: usize,
+    type_info: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for RefSet<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        RefSet {
+            words: (self.words.clone()),
+            len: (self.len),
+            type_info: (PhantomData),
+        }
+    }
+}
+
+impl<T> Default for RefSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PartialEq for RefSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        self.len == other.len
+            && shorter.iter().zip(longer.iter()).all(|(a, b)| a == b)
+            && longer[shorter.len()..].iter().all(|word| *word == 0)
+    }
+}
+impl<T> Eq for RefSet<T> {}
+
+impl<T> FromIterator<ValueRef<T>> for RefSet<T> {
+    fn from_iter<I: IntoIterator<Item = ValueRef<T>>>(iter: I) -> Self {
+        let mut set = RefSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T> Extend<ValueRef<T>> for RefSet<T> {
+    fn extend<I: IntoIterator<Item = ValueRef<T>>>(&mut self, iter: I) {
+        for reference in iter {
+            self.insert(reference);
+        }
+    }
+}
+
+#[inline]
+fn split<T>(reference: ValueRef<T>) -> (usize, u64) {
+    let index = reference.index.get();
+    (index / BITS, 1 << (index % BITS))
+}
+
+/// Iterates over the refs stored in `bits`, the `word`th word of a [`RefSet`].
+#[inline]
+fn refs_of_word<T>(word: usize, mut bits: u64) -> impl Iterator<Item = ValueRef<T>> {
+    std::iter::from_fn(move || {
+        if bits == 0 {
+            return None;
+        }
+        let bit = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+        Some(ValueRef::new(word * BITS + bit))
+    })
+}
+
+impl<T> RefSet<T> {
+    /// Creates a new, empty [`RefSet`].
+    #[inline]
+    pub const fn new() -> RefSet<T> {
+        RefSet {
+            words: (Vec::new()),
+            len: 0,
+            type_info: (PhantomData),
+        }
+    }
+
+    /// Creates a new, empty [`RefSet`] that can store all positions below `positions` without reallocation.
+    #[inline]
+    pub fn with_capacity(positions: usize) -> RefSet<T> {
+        RefSet {
+            words: (Vec::with_capacity(positions.div_ceil(BITS))),
+            len: 0,
+            type_info: (PhantomData),
+        }
+    }
+
+    /// Returns the number of stored refs.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no refs are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `reference`. Returns false if it was already stored.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn insert(&mut self, reference: impl Into<ValueRef<T>>) -> bool {
+        let (word, bit) = split(reference.into());
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let inserted = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        self.len += inserted as usize;
+        inserted
+    }
+
+    /// Removes `reference`. Returns false if it wasn't stored.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> bool {
+        let (word, bit) = split(reference.into());
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+        let removed = *word & bit != 0;
+        *word &= !bit;
+        self.len -= removed as usize;
+        removed
+    }
+
+    /// Returns true if `reference` is stored.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn contains(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        let (word, bit) = split(reference.into());
+        self.words.get(word).is_some_and(|word| word & bit != 0)
+    }
+
+    /// Removes all refs. This keeps the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Iterates over all stored refs in ascending order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ValueRef<T>> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word, bits)| refs_of_word(word, *bits))
+    }
+
+    /// Removes all refs and iterates over them in ascending order.
+    #[inline]
+    pub fn drain(&mut self) -> impl Iterator<Item = ValueRef<T>> {
+        self.len = 0;
+        std::mem::take(&mut self.words)
+            .into_iter()
+            .enumerate()
+            .flat_map(|(word, bits)| refs_of_word(word, bits))
+    }
+
+    #[inline]
+    fn recount(&mut self) {
+        self.len = self
+            .words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum();
+    }
+
+    /// Adds all refs of `other` to this set.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the greatest position stored in `other`
+    pub fn union_with(&mut self, other: &RefSet<T>) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+        self.recount();
+    }
+
+    /// Removes all refs that are not stored in `other`.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the greatest position stored in this set
+    pub fn intersect_with(&mut self, other: &RefSet<T>) {
+        self.words.truncate(other.words.len());
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= other;
+        }
+        self.recount();
+    }
+
+    /// Removes all refs that are stored in `other`.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the greatest position stored in this set
+    pub fn difference_with(&mut self, other: &RefSet<T>) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other;
+        }
+        self.recount();
+    }
+
+    /// Returns a new set with all refs stored in this set or `other`.
+    #[inline]
+    pub fn union(&self, other: &RefSet<T>) -> RefSet<T> {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+
+    /// Returns a new set with all refs stored in both this set and `other`.
+    #[inline]
+    pub fn intersection(&self, other: &RefSet<T>) -> RefSet<T> {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+
+    /// Returns a new set with all refs stored in this set but not in `other`.
+    #[inline]
+    pub fn difference(&self, other: &RefSet<T>) -> RefSet<T> {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RefSet;
+    use crate::ValueRef;
+
+    #[test]
+    fn test_set_operations() {
+        let a: RefSet<()> = [0, 3, 64, 200].into_iter().map(ValueRef::new).collect();
+        let b: RefSet<()> = [3, 64, 65].into_iter().map(ValueRef::new).collect();
+
+        let mut c = a.clone();
+        assert!(c.remove(ValueRef::new(200)));
+        assert!(!c.remove(ValueRef::new(200)));
+        assert!(!c.remove(ValueRef::new(5000)));
+        // trailing empty words don't affect equality
+        assert_eq!(c, [0, 3, 64].into_iter().map(ValueRef::new).collect());
+
+        let indices = |set: RefSet<()>| set.iter().map(|r| r.index.get()).collect::<Vec<_>>();
+        assert_eq!(indices(a.union(&b)), vec![0, 3, 64, 65, 200]);
+        assert_eq!(indices(a.intersection(&b)), vec![3, 64]);
+        assert_eq!(indices(a.difference(&b)), vec![0, 200]);
+        assert_eq!(a.difference(&b).len(), 2);
+        assert!(a.intersection(&RefSet::new()).is_empty());
+    }
+}
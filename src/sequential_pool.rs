@@ -0,0 +1,177 @@
+//! This module implements [`SequentialValuePool<T>`], a pool without free list for build-then-freeze workloads.
+use crate::{ValuePool, ValueRef};
+
+/// A [`SequentialValuePool<T>`] is a bump allocator with the interface of a [`ValuePool<T>`]: it has no free list,
+/// so values can only be removed from the end and every pushed value gets a greater [`ValueRef<T>`] than the values stored before it.
+/// Without empty positions, the values are stored densely and [`SequentialValuePool::push`] only appends.
+///
+/// # Note
+/// The positions freed by [`SequentialValuePool::pop`] and [`SequentialValuePool::truncate`] are reused by the next pushes,
+/// so refs to removed values point to the values pushed after them.
+/// Once built, it can be turned into a regular [`ValuePool<T>`], keeping all refs valid.
+/// ```
+/// use value_pool::{sequential_pool::SequentialValuePool, ValuePool};
+///
+/// let mut pool: SequentialValuePool<&str> = SequentialValuePool::new();
+/// let a = pool.push("a");
+/// let b = pool.push("b");
+/// assert!(a < b);
+/// assert_eq!(pool.as_slice(), &["a", "b"]);
+/// assert_eq!(pool.pop(), Some("b"));
+/// assert_eq!(pool.next_push_ref(), b);
+///
+/// let mut frozen: ValuePool<&str> = pool.into();
+/// assert_eq!(frozen.get(a), Some(&"a"));
+/// assert_eq!(frozen.push("c"), b);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SequentialValuePool<T> {
+    store: Vec<T>,
+}
+
+impl<T> Default for SequentialValuePool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SequentialValuePool<T> {
+    /// Creates a new, empty [`SequentialValuePool`].
+    #[inline]
+    pub const fn new() -> SequentialValuePool<T> {
+        SequentialValuePool {
+            store: (Vec::new()),
+        }
+    }
+
+    /// Creates a new, empty [`SequentialValuePool`] with space for at least `capacity` values.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> SequentialValuePool<T> {
+        SequentialValuePool {
+            store: (Vec::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the number of values stored in this [`SequentialValuePool`].
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Returns true if no value is stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Returns the [`ValueRef<T>`] the next call of [`SequentialValuePool::push`] will return.
+    #[inline]
+    pub fn next_push_ref(&self) -> ValueRef<T> {
+        ValueRef::new(self.store.len())
+    }
+
+    /// Appends `value` and returns its position.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let reference = self.next_push_ref();
+        self.store.push(value);
+        reference
+    }
+
+    /// Removes the last value and returns it. The next push reuses its position.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.store.pop()
+    }
+
+    /// Removes all values at `reference` and after it. The next push reuses the position `reference`.
+    #[inline]
+    pub fn truncate(&mut self, reference: impl Into<ValueRef<T>>) {
+        let reference: ValueRef<T> = reference.into();
+        self.store.truncate(reference.index.get());
+    }
+
+    /// Gets a borrow of the value at `reference`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        let reference: ValueRef<T> = reference.into();
+        self.store.get(reference.index.get())
+    }
+
+    /// Gets a mut borrow of the value at `reference`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_mut(&mut self, reference: impl Into<ValueRef<T>>) -> Option<&mut T> {
+        let reference: ValueRef<T> = reference.into();
+        self.store.get_mut(reference.index.get())
+    }
+
+    /// Returns all values, in the order of their refs.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.store
+    }
+
+    /// Returns all values mutably, in the order of their refs.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.store
+    }
+
+    /// Iterates over all values with their refs, in order.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (ValueRef<T>, &T)> + ExactSizeIterator {
+        self.store
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (ValueRef::new(index), value))
+    }
+}
+
+impl<T> From<SequentialValuePool<T>> for ValuePool<T> {
+    /// Turns the [`SequentialValuePool<T>`] into a [`ValuePool<T>`], every value stays at its position.
+    ///
+    /// # Complexity
+    /// `O(n)`
+    fn from(pool: SequentialValuePool<T>) -> Self {
+        let mut converted = ValuePool::new();
+        converted.store = pool.store.into_iter().map(Some).collect();
+        #[cfg(feature = "slot-stats")]
+        converted.reuse_counts.resize(converted.store.len(), 0);
+        converted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SequentialValuePool;
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_refs_are_monotonic() {
+        let mut pool: SequentialValuePool<u32> = SequentialValuePool::with_capacity(4);
+        let refs: Vec<_> = (0..5).map(|value| pool.push(value * 10)).collect();
+        assert!(refs.windows(2).all(|pair| pair[0] < pair[1]));
+        *pool.get_mut(refs[1]).unwrap() += 1;
+        pool.truncate(refs[3]);
+        assert_eq!(pool.get(refs[3]), None);
+        assert_eq!(pool.next_push_ref(), refs[3]);
+        assert_eq!(pool.iter().next_back(), Some((refs[2], &20)));
+
+        let frozen: ValuePool<u32> = pool.into();
+        assert_eq!(frozen.element_count(), 3);
+        assert_eq!(frozen.get(ValueRef::new(1)), Some(&11));
+        assert_eq!(frozen.waiting_positions(), 0);
+    }
+}
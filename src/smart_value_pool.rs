@@ -1,5 +1,6 @@
 //! This module implements [`SmartValuePool<T>`] which can automatically call a function if a method call changes it state from empty to one element or vice versa.
 use std::{
+    fmt::Display,
     marker::PhantomData,
     ops::{Deref, DerefMut},
 };
@@ -39,12 +40,43 @@ use crate::{ValuePool, ValueRef};
 /// assert_eq!(&text, "Start: |Called on_empty_push||Called on_empty|");
 /// assert_eq!(pool.waiting_positions(), 3);
 /// ```
+///
+/// # Thread safety
+/// [`SmartValuePool<T, O>`] is [`Send`] and [`Sync`] if [`ValuePool<T>`] is, independent of `O`.
 #[derive(Debug)]
 pub struct SmartValuePool<T, O> {
     pool: ValuePool<T>,
     on_empty: fn(&mut ValuePool<T>, &mut O),
     on_empty_push: fn(&mut ValuePool<T>, ValueRef<T>, &mut O),
-    object_type: PhantomData<O>,
+    // `O` is never stored, so it shouldn't affect `Send` and `Sync`
+    object_type: PhantomData<fn(&mut O)>,
+    poisoned: bool,
+}
+
+/// Returned by the `try_smart_*` methods of a poisoned [`SmartValuePool<T, O>`].
+/// See [`SmartValuePool::is_poisoned`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoisonError;
+
+impl Display for PoisonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a callback of this SmartValuePool panicked")
+    }
+}
+
+impl std::error::Error for PoisonError {}
+
+/// Marks the pool as poisoned if it gets dropped while a callback panics.
+struct PoisonGuard<'a> {
+    poisoned: &'a mut bool,
+}
+
+impl Drop for PoisonGuard<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            *self.poisoned = true;
+        }
+    }
 }
 
 impl<T, O> Deref for SmartValuePool<T, O> {
@@ -73,14 +105,47 @@ impl<T, O> SmartValuePool<T, O> {
             on_empty,
             on_empty_push,
             object_type: (PhantomData),
+            poisoned: false,
         }
     }
+
+    /// Returns true if `on_empty` or `on_empty_push` panicked during a `smart_*` call.
+    /// The pool and the given object could be in a half-updated state.
+    /// The `try_smart_*` methods return [`PoisonError`] until [`SmartValuePool::clear_poison`] is called.
+    ///
+    /// Note: Methods of the underlying [`ValuePool<T>`] (reached trough [`Deref`]) don't check this flag.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Clears the poisoned state, see [`SmartValuePool::is_poisoned`].
+    #[inline]
+    pub fn clear_poison(&mut self) {
+        self.poisoned = false;
+    }
+
+    #[inline]
+    fn call_on_empty(&mut self, object: &mut O) {
+        let _guard = PoisonGuard {
+            poisoned: &mut self.poisoned,
+        };
+        (self.on_empty)(&mut self.pool, object);
+    }
+
+    #[inline]
+    fn call_on_empty_push(&mut self, reference: ValueRef<T>, object: &mut O) {
+        let _guard = PoisonGuard {
+            poisoned: &mut self.poisoned,
+        };
+        (self.on_empty_push)(&mut self.pool, reference, object);
+    }
     /// Same as [`ValuePool<T>::push`] but it will call the previously given `on_empty_push` if needed
     #[inline]
     pub fn smart_push(&mut self, value: T, object: &mut O) -> ValueRef<T> {
         let tmp = self.pool.push(value);
         if self.pool.element_count() == 1 {
-            (self.on_empty_push)(&mut self.pool, tmp, object);
+            self.call_on_empty_push(tmp, object);
         }
         tmp
     }
@@ -90,7 +155,7 @@ impl<T, O> SmartValuePool<T, O> {
     pub fn smart_take(&mut self, reference: ValueRef<T>, object: &mut O) -> Option<T> {
         let tmp = self.pool.take(reference);
         if self.is_empty() {
-            (self.on_empty)(&mut self.pool, object);
+            self.call_on_empty(object);
         }
         tmp
     }
@@ -107,7 +172,7 @@ impl<T, O> SmartValuePool<T, O> {
     ) -> Option<T> {
         let tmp = self.take_unchecked(reference);
         if self.is_empty() {
-            (self.on_empty)(&mut self.pool, object);
+            self.call_on_empty(object);
         }
         tmp
     }
@@ -117,7 +182,75 @@ impl<T, O> SmartValuePool<T, O> {
     pub fn smart_remove(&mut self, reference: impl Into<ValueRef<T>>, object: &mut O) {
         self.remove(reference);
         if self.is_empty() {
-            (self.on_empty)(&mut self.pool, object);
+            self.call_on_empty(object);
         }
     }
+
+    /// Same as [`SmartValuePool::smart_push`] but returns [`PoisonError`] if this pool [is poisoned](SmartValuePool::is_poisoned).
+    #[inline]
+    pub fn try_smart_push(&mut self, value: T, object: &mut O) -> Result<ValueRef<T>, PoisonError> {
+        if self.poisoned {
+            return Err(PoisonError);
+        }
+        Ok(self.smart_push(value, object))
+    }
+
+    /// Same as [`SmartValuePool::smart_take`] but returns [`PoisonError`] if this pool [is poisoned](SmartValuePool::is_poisoned).
+    #[inline]
+    pub fn try_smart_take(
+        &mut self,
+        reference: ValueRef<T>,
+        object: &mut O,
+    ) -> Result<Option<T>, PoisonError> {
+        if self.poisoned {
+            return Err(PoisonError);
+        }
+        Ok(self.smart_take(reference, object))
+    }
+
+    /// Same as [`SmartValuePool::smart_remove`] but returns [`PoisonError`] if this pool [is poisoned](SmartValuePool::is_poisoned).
+    #[inline]
+    pub fn try_smart_remove(
+        &mut self,
+        reference: impl Into<ValueRef<T>>,
+        object: &mut O,
+    ) -> Result<(), PoisonError> {
+        if self.poisoned {
+            return Err(PoisonError);
+        }
+        self.smart_remove(reference, object);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PoisonError, SmartValuePool};
+    use crate::{ValuePool, ValueRef};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    fn on_empty(_pool: &mut ValuePool<u32>, _object: &mut ()) {}
+    fn panicking_on_empty_push(
+        _pool: &mut ValuePool<u32>,
+        _reference: ValueRef<u32>,
+        _object: &mut (),
+    ) {
+        panic!("on_empty_push failed");
+    }
+
+    #[test]
+    fn test_panicking_callback_poisons() {
+        let mut pool: SmartValuePool<u32, ()> =
+            SmartValuePool::make_smart(ValuePool::new(), on_empty, panicking_on_empty_push);
+        assert!(!pool.is_poisoned());
+
+        let res = catch_unwind(AssertUnwindSafe(|| pool.smart_push(1, &mut ())));
+        assert!(res.is_err());
+        assert!(pool.is_poisoned());
+        assert_eq!(pool.try_smart_push(2, &mut ()), Err(PoisonError));
+        assert_eq!(pool.element_count(), 1);
+
+        pool.clear_poison();
+        assert_eq!(pool.try_smart_take(ValueRef::new(0), &mut ()), Ok(Some(1)));
+    }
 }
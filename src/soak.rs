@@ -0,0 +1,135 @@
+//! This module implements [`Soak`], a soak-test harness that applies random operations to a [`HandleContainer<T>`] and checks it against a shadow model.
+use crate::handle_container::HandleContainer;
+use std::fmt::Debug;
+
+/// One operation applied by [`Soak::run`]. Positions index the live values of the shadow model, in insertion order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoakOp {
+    /// Inserts a new value.
+    Insert,
+    /// Removes the value at the position and checks that the removed value is the expected one.
+    Take(usize),
+    /// Removes the value at the position without looking at it.
+    Remove(usize),
+    /// Swaps the values at the two positions through [`HandleContainer::get_mut`].
+    Swap(usize, usize),
+}
+
+/// A [`Soak`] applies a random but reproducible sequence of valid [`SoakOp`]s to a [`HandleContainer<T>`].
+/// After every step it checks that every live handle still returns its value, then calls a custom invariant check.
+/// On a mismatch it panics with the seed and step, so the failing sequence can be replayed.
+/// Downstream wrappers that implement [`HandleContainer<T>`] can reuse it for their own types.
+/// ```
+/// use value_pool::{soak::Soak, ValuePool};
+///
+/// let mut pool: ValuePool<u64> = ValuePool::new();
+/// Soak::new(42).steps(500).run(&mut pool, |random| random, |pool, live| {
+///     assert_eq!(pool.element_count(), live.len());
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Soak {
+    seed: u64,
+    steps: usize,
+}
+
+impl Soak {
+    /// Creates a [`Soak`] that runs 1000 steps, with operations chosen by `seed`.
+    #[inline]
+    pub const fn new(seed: u64) -> Soak {
+        Soak { seed, steps: 1000 }
+    }
+
+    /// Sets the number of operations to apply.
+    #[inline]
+    pub const fn steps(mut self, steps: usize) -> Soak {
+        self.steps = steps;
+        self
+    }
+
+    /// Applies the operations to `container`. New values are created by `make_value` from a random number.
+    /// `invariant` is called after every step with the container and the live handles and values of the shadow model.
+    pub fn run<T, C>(
+        &self,
+        container: &mut C,
+        mut make_value: impl FnMut(u64) -> T,
+        mut invariant: impl FnMut(&C, &[(C::Handle, T)]),
+    ) where
+        T: Clone + PartialEq + Debug,
+        C: HandleContainer<T>,
+    {
+        // xorshift64*, seeded so that a seed of 0 works as well
+        let mut state = self.seed ^ 0x9E37_79B9_7F4A_7C15;
+        let mut random = move || {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        };
+        let mut live: Vec<(C::Handle, T)> = Vec::new();
+        for step in 0..self.steps {
+            let op = match (live.len() as u64, random() % 5) {
+                (0, _) | (_, 0 | 1) => SoakOp::Insert,
+                (len, 2) => SoakOp::Take((random() % len) as usize),
+                (len, 3) => SoakOp::Remove((random() % len) as usize),
+                (len, _) => SoakOp::Swap((random() % len) as usize, (random() % len) as usize),
+            };
+            let context = format!(
+                "soak with seed {} failed at step {step} ({op:?})",
+                self.seed
+            );
+            match op {
+                SoakOp::Insert => {
+                    let value = make_value(random());
+                    live.push((container.insert(value.clone()), value));
+                }
+                SoakOp::Take(position) => {
+                    let (handle, value) = live.remove(position);
+                    assert_eq!(container.remove(handle), Some(value), "{context}");
+                }
+                SoakOp::Remove(position) => {
+                    let (handle, _) = live.remove(position);
+                    assert!(container.remove(handle).is_some(), "{context}");
+                }
+                SoakOp::Swap(first, second) => {
+                    let (first_handle, first_value) = live[first].clone();
+                    let (second_handle, second_value) = live[second].clone();
+                    *container.get_mut(first_handle).expect(&context) = second_value.clone();
+                    *container.get_mut(second_handle).expect(&context) = first_value.clone();
+                    live[first].1 = second_value;
+                    live[second].1 = first_value;
+                }
+            }
+            for (handle, value) in &live {
+                assert_eq!(container.get(*handle), Some(value), "{context}");
+            }
+            invariant(container, &live);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Soak;
+    use crate::{dirty_pool::DirtyPool, ValuePool};
+
+    #[test]
+    fn test_soak_pools() {
+        for seed in 0..8 {
+            let mut pool: ValuePool<u32> = ValuePool::new();
+            let mut steps = 0;
+            Soak::new(seed).steps(300).run(
+                &mut pool,
+                |random| random as u32,
+                |pool, live| {
+                    steps += 1;
+                    assert_eq!(pool.element_count(), live.len());
+                },
+            );
+            assert_eq!(steps, 300);
+
+            let mut dirty: DirtyPool<String> = DirtyPool::new();
+            Soak::new(seed).run(&mut dirty, |random| random.to_string(), |_, _| {});
+        }
+    }
+}
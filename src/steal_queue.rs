@@ -0,0 +1,323 @@
+//! This module implements [`StealQueue<T>`], a work-stealing deque whose tasks are stored inside [`ValuePool<T>`]s.
+//! The owner pushes and pops at the back, any number of [`Stealer`]s take from the front.
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::{
+    sync::{AtomicUsize, Mutex, MutexGuard, Ordering},
+    ValuePool, ValueRef,
+};
+
+/// Tasks in a [`ValuePool<T>`], ordered by a queue of their positions.
+#[derive(Debug)]
+struct Tasks<T> {
+    pool: ValuePool<T>,
+    // front is the oldest task
+    order: VecDeque<ValueRef<T>>,
+}
+
+impl<T> Tasks<T> {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Tasks<T> {
+        Tasks {
+            pool: (ValuePool::with_capacity(capacity)),
+            order: (VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    #[inline]
+    fn push_back(&mut self, task: T) {
+        let reference = self.pool.push(task);
+        self.order.push_back(reference);
+    }
+
+    #[inline]
+    fn pop_back(&mut self) -> Option<T> {
+        let reference = self.order.pop_back()?;
+        self.pool.take(reference)
+    }
+
+    #[inline]
+    fn pop_front(&mut self) -> Option<T> {
+        let reference = self.order.pop_front()?;
+        self.pool.take(reference)
+    }
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    // the oldest tasks, the only ones stealers can reach
+    stealable: Mutex<Tasks<T>>,
+    // the length of `stealable`, so the owner can check it without locking; only written while it's locked
+    stealable_len: AtomicUsize,
+    // the number of tasks in both ends
+    len: AtomicUsize,
+}
+
+impl<T> Shared<T> {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Tasks<T>> {
+        self.stealable.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A [`StealQueue<T>`] is owned by one worker, which [pushes](StealQueue::push) and [pops](StealQueue::pop) tasks in LIFO order.
+/// Other workers [steal](Stealer::steal) the oldest tasks trough [`Stealer`]s.
+///
+/// The queue is split into two ends, each storing its tasks in a [`ValuePool<T>`], so the positions of finished tasks get reused:
+/// The newest tasks sit in the owner end, which only the owner touches and which needs no lock.
+/// The oldest tasks sit in the steal end, which is guarded by a [`Mutex`] that the owner only takes
+/// to refill it or once its own end is empty.
+/// Whenever the owner pushes or pops while the steal end is empty, the older half of its tasks moves to the steal end.
+/// ```
+/// use value_pool::steal_queue::StealQueue;
+///
+/// let mut queue: StealQueue<u32> = StealQueue::new();
+/// let stealer = queue.stealer();
+/// queue.push(1);
+/// queue.push(2);
+/// queue.push(3);
+///
+/// let stolen = std::thread::spawn(move || stealer.steal()).join().unwrap();
+/// assert_eq!(stolen, Some(1));
+/// assert_eq!(queue.pop(), Some(3));
+/// assert_eq!(queue.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct StealQueue<T> {
+    // the newest tasks, front is the oldest of them
+    local: Tasks<T>,
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Default for StealQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> StealQueue<T> {
+    /// Creates a new, empty [`StealQueue`].
+    #[inline]
+    pub fn new() -> StealQueue<T> {
+        StealQueue::with_capacity(0)
+    }
+
+    /// Creates a new, empty [`StealQueue`] with space for at least `capacity` tasks in the owner end.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> StealQueue<T> {
+        StealQueue {
+            local: (Tasks::with_capacity(capacity)),
+            shared: (Arc::new(Shared {
+                stealable: (Mutex::new(Tasks::with_capacity(capacity / 2))),
+                stealable_len: (AtomicUsize::new(0)),
+                len: (AtomicUsize::new(0)),
+            })),
+        }
+    }
+
+    /// Returns a new [`Stealer`] for this queue.
+    #[inline]
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            shared: (Arc::clone(&self.shared)),
+        }
+    }
+
+    /// Returns the number of queued tasks. It can be outdated as soon as it's returned if [`Stealer`]s are active.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shared.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if no tasks are queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves the older half of the owner end to the steal end, if the steal end is empty.
+    #[inline]
+    fn refill(&mut self) {
+        if self.shared.stealable_len.load(Ordering::Relaxed) != 0 || self.local.len() == 0 {
+            return;
+        }
+        let count = self.local.len().div_ceil(2);
+        let mut stealable = self.shared.lock();
+        for _ in 0..count {
+            stealable.push_back(self.local.pop_front().expect("Tasks to be left"));
+        }
+        self.shared
+            .stealable_len
+            .store(stealable.len(), Ordering::Relaxed);
+    }
+
+    /// Pushes `task` to the back of the queue.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized), `O(n)` if the steal end gets refilled
+    #[inline]
+    pub fn push(&mut self, task: T) {
+        self.local.push_back(task);
+        // counted before stealers can reach it, so `len` never underflows
+        self.shared.len.fetch_add(1, Ordering::Relaxed);
+        self.refill();
+    }
+
+    /// Pops the newest task. Only locks if the owner end is empty or the steal end gets refilled.
+    ///
+    /// # Complexity
+    /// `O(1)`, `O(n)` if the steal end gets refilled
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.local.len() > 1 {
+            self.refill();
+        }
+        let task = match self.local.pop_back() {
+            Some(task) => task,
+            None => {
+                let mut stealable = self.shared.lock();
+                let task = stealable.pop_back()?;
+                self.shared
+                    .stealable_len
+                    .store(stealable.len(), Ordering::Relaxed);
+                task
+            }
+        };
+        self.shared.len.fetch_sub(1, Ordering::Relaxed);
+        Some(task)
+    }
+}
+
+/// Takes the oldest tasks of a [`StealQueue<T>`] from its steal end. Can be cloned and sent to other threads.
+#[derive(Debug)]
+pub struct Stealer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for Stealer<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Stealer {
+            shared: (Arc::clone(&self.shared)),
+        }
+    }
+}
+
+impl<T> Stealer<T> {
+    /// Returns the number of queued tasks in both ends; only the ones in the steal end can be stolen right now.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shared.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if no tasks are queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes up to `count` of the oldest tasks from the steal end.
+    fn take_oldest(&self, count: impl FnOnce(usize) -> usize) -> Vec<T> {
+        let mut stealable = self.shared.lock();
+        let count = count(stealable.len());
+        let stolen: Vec<T> = (0..count).filter_map(|_| stealable.pop_front()).collect();
+        self.shared
+            .stealable_len
+            .store(stealable.len(), Ordering::Relaxed);
+        drop(stealable);
+        self.shared.len.fetch_sub(stolen.len(), Ordering::Relaxed);
+        stolen
+    }
+
+    /// Steals the oldest task.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn steal(&self) -> Option<T> {
+        self.take_oldest(|_| 1).pop()
+    }
+
+    /// Steals up to half of the tasks in the steal end (at least one, if any) and pushes them to `destination`.
+    /// Returns the number of stolen tasks.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the number of stolen tasks
+    pub fn steal_batch(&self, destination: &mut StealQueue<T>) -> usize {
+        if Arc::ptr_eq(&self.shared, &destination.shared) {
+            return 0;
+        }
+        let stolen = self.take_oldest(|len| len.div_ceil(2));
+        let count = stolen.len();
+        for task in stolen {
+            destination.push(task);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StealQueue;
+
+    #[test]
+    fn test_steal_batch() {
+        let mut queue: StealQueue<u32> = StealQueue::new();
+        let mut other: StealQueue<u32> = StealQueue::new();
+        for task in 0..5 {
+            queue.push(task);
+        }
+        let stealer = queue.stealer();
+        assert_eq!(stealer.steal_batch(&mut queue), 0);
+        // only the first task got moved to the steal end so far
+        assert_eq!(stealer.steal_batch(&mut other), 1);
+        assert_eq!(other.pop(), Some(0));
+
+        // popping refills the empty steal end with the older half
+        assert_eq!(queue.pop(), Some(4));
+        assert_eq!(stealer.steal_batch(&mut other), 1);
+        assert_eq!(stealer.steal(), Some(2));
+        assert_eq!(stealer.steal(), None);
+        assert_eq!(queue.pop(), Some(3));
+        assert!(stealer.is_empty() && queue.is_empty());
+        assert_eq!(other.pop(), Some(1));
+    }
+
+    #[test]
+    fn test_every_task_is_taken_once() {
+        let mut queue: StealQueue<u64> = StealQueue::new();
+        let stealer = queue.stealer();
+        let stolen = std::thread::scope(|s| {
+            let thieves: Vec<_> = (0..3)
+                .map(|_| {
+                    let stealer = stealer.clone();
+                    s.spawn(move || {
+                        let mut sum = 0;
+                        for _ in 0..2000 {
+                            sum += stealer.steal().unwrap_or(0);
+                        }
+                        sum
+                    })
+                })
+                .collect();
+            let mut popped = 0;
+            for task in 1..=3000 {
+                queue.push(task);
+                if task % 3 == 0 {
+                    popped += queue.pop().unwrap_or(0);
+                }
+            }
+            while let Some(task) = queue.pop() {
+                popped += task;
+            }
+            popped + thieves.into_iter().map(|t| t.join().unwrap()).sum::<u64>()
+        });
+        assert_eq!(stolen, (1..=3000).sum::<u64>());
+        assert!(queue.is_empty());
+    }
+}
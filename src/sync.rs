@@ -0,0 +1,43 @@
+//! Synchronization primitives used by the concurrent pools.
+//!
+//! Building the unit tests with `RUSTFLAGS="--cfg loom"` swaps them for the models of the [`loom`](https://docs.rs/loom) crate,
+//! so the model tests can explore all interleavings. Loom models `SeqCst` as `AcqRel`, so only the parts that don't
+//! rely on the total order of `SeqCst` operations are modeled, e.g. how [`ReadMostlyPool`](crate::read_mostly_pool::ReadMostlyPool)
+//! publishes and frees its segments.
+//! The `concurrency-checks` feature turns on assertions of internal invariants, see [`concurrency_check!`].
+
+#[cfg(all(test, loom))]
+pub(crate) use loom::sync::{
+    atomic::{AtomicPtr, AtomicUsize, Ordering},
+    Mutex, MutexGuard,
+};
+#[cfg(not(all(test, loom)))]
+pub(crate) use std::sync::{
+    atomic::{AtomicPtr, AtomicUsize, Ordering},
+    Mutex, MutexGuard,
+};
+
+/// Reads an [`AtomicPtr`] trough exclusive access.
+#[cfg(not(all(test, loom)))]
+#[inline]
+pub(crate) fn load_mut<T>(atomic: &mut AtomicPtr<T>) -> *mut T {
+    *atomic.get_mut()
+}
+
+/// Reads an [`AtomicPtr`] trough exclusive access.
+#[cfg(all(test, loom))]
+#[inline]
+pub(crate) fn load_mut<T>(atomic: &mut AtomicPtr<T>) -> *mut T {
+    atomic.with_mut(|ptr| *ptr)
+}
+
+/// Asserts an invariant of a concurrent data structure if the `concurrency-checks` feature is enabled.
+/// Otherwise, the condition isn't evaluated.
+macro_rules! concurrency_check {
+    ($cond:expr, $($arg:tt)+) => {
+        if cfg!(feature = "concurrency-checks") {
+            assert!($cond, $($arg)+);
+        }
+    };
+}
+pub(crate) use concurrency_check;
@@ -0,0 +1,98 @@
+//! This module implements [`SyncValueRef<T>`], a [`ValueRef<T>`] bundled with a shared, lock protected [`ValuePool<T>`].
+use std::sync::{Arc, RwLock};
+
+use crate::{ValuePool, ValueRef};
+
+/// A [`SyncValueRef<T>`] combines a [`ValueRef<T>`] with an [`Arc<RwLock<ValuePool<T>>>`], so it can be
+/// passed to other threads and accessed without carrying the pool around separately.
+/// ```
+/// use std::sync::{Arc, RwLock};
+/// use value_pool::{ValuePool, sync_value_ref::SyncValueRef};
+///
+/// let pool = Arc::new(RwLock::new(ValuePool::new()));
+/// let counter = SyncValueRef::push(&pool, 0u32);
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let counter = counter.clone();
+///         std::thread::spawn(move || counter.write(|value| *value += 1))
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// assert_eq!(counter.read(|value| *value), Some(4));
+/// ```
+///
+/// Note: A poisoned lock is ignored, the pool is accessed anyway.
+#[derive(Debug)]
+pub struct SyncValueRef<T> {
+    pool: Arc<RwLock<ValuePool<T>>>,
+    reference: ValueRef<T>,
+}
+
+impl<T> Clone for SyncValueRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SyncValueRef {
+            pool: (Arc::clone(&self.pool)),
+            reference: (self.reference),
+        }
+    }
+}
+
+impl<T> SyncValueRef<T> {
+    /// Creates a [`SyncValueRef`] that points to `reference` in `pool`.
+    #[inline]
+    pub fn new(
+        pool: Arc<RwLock<ValuePool<T>>>,
+        reference: impl Into<ValueRef<T>>,
+    ) -> SyncValueRef<T> {
+        SyncValueRef {
+            pool,
+            reference: (reference.into()),
+        }
+    }
+
+    /// Pushes `value` into `pool` and returns a [`SyncValueRef`] to it.
+    #[inline]
+    pub fn push(pool: &Arc<RwLock<ValuePool<T>>>, value: T) -> SyncValueRef<T> {
+        let reference = pool.write().unwrap_or_else(|e| e.into_inner()).push(value);
+        SyncValueRef::new(Arc::clone(pool), reference)
+    }
+
+    /// Returns the underlying [`ValueRef<T>`].
+    #[inline]
+    pub fn reference(&self) -> ValueRef<T> {
+        self.reference
+    }
+
+    /// Returns the shared pool.
+    #[inline]
+    pub fn pool(&self) -> &Arc<RwLock<ValuePool<T>>> {
+        &self.pool
+    }
+
+    /// Calls `f` with a borrow of the value, while holding a read lock. Returns [`None`] if no value is stored at the position.
+    #[inline]
+    pub fn read<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        let pool = self.pool.read().unwrap_or_else(|e| e.into_inner());
+        pool.get(self.reference).map(f)
+    }
+
+    /// Calls `f` with a mut borrow of the value, while holding a write lock. Returns [`None`] if no value is stored at the position.
+    #[inline]
+    pub fn write<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut pool = self.pool.write().unwrap_or_else(|e| e.into_inner());
+        pool.get_mut(self.reference).map(f)
+    }
+
+    /// Takes the value out of the pool, see [`ValuePool::take`].
+    #[inline]
+    pub fn take(&self) -> Option<T> {
+        self.pool
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take(self.reference)
+    }
+}
@@ -0,0 +1,78 @@
+//! This module implements [`ThreadLocalPool<T>`] and the [`thread_local_pool!`](crate::thread_local_pool!) macro which give every thread its own [`ValuePool<T>`].
+use std::{cell::RefCell, thread::LocalKey};
+
+use crate::{ValuePool, ValueRef};
+
+/// Declares a `static` [`ThreadLocalPool<T>`]. Every thread lazily gets its own, initially empty [`ValuePool<T>`].
+/// ```
+/// use value_pool::thread_local_pool;
+///
+/// thread_local_pool!(static TOKENS: String);
+///
+/// let reference = TOKENS.push("fn".to_string());
+/// assert_eq!(TOKENS.with(|pool| pool.get(reference).cloned()), Some("fn".to_string()));
+///
+/// // other threads have their own pool
+/// std::thread::spawn(move || {
+///     assert!(TOKENS.with(|pool| pool.is_empty()));
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[macro_export]
+macro_rules! thread_local_pool {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty) => {
+        $(#[$attr])*
+        $vis static $name: $crate::thread_local_pool::ThreadLocalPool<$t> = {
+            ::std::thread_local! {
+                static POOL: ::std::cell::RefCell<$crate::ValuePool<$t>> =
+                    const { ::std::cell::RefCell::new($crate::ValuePool::new()) };
+            }
+            $crate::thread_local_pool::ThreadLocalPool::new(&POOL)
+        };
+    };
+}
+
+/// A handle to a per-thread [`ValuePool<T>`]. Usually declared with [`thread_local_pool!`](crate::thread_local_pool!).
+///
+/// Note: [`ValueRef<T>`]s are only meaningful on the thread that created them.
+/// Calling [`ThreadLocalPool::with`] inside of `f` of another [`ThreadLocalPool::with`] on the same pool panics.
+#[derive(Debug)]
+pub struct ThreadLocalPool<T: 'static> {
+    key: &'static LocalKey<RefCell<ValuePool<T>>>,
+}
+
+impl<T: 'static> ThreadLocalPool<T> {
+    /// Creates a [`ThreadLocalPool`] from a `thread_local!` key. Usually, [`thread_local_pool!`](crate::thread_local_pool!) is more convenient.
+    #[inline]
+    pub const fn new(key: &'static LocalKey<RefCell<ValuePool<T>>>) -> ThreadLocalPool<T> {
+        ThreadLocalPool { key }
+    }
+
+    /// Runs `f` with the [`ValuePool<T>`] of the current thread.
+    ///
+    /// # Panic
+    /// Panics if called from within `f` of another call on the same [`ThreadLocalPool`].
+    #[inline]
+    pub fn with<R>(&self, f: impl FnOnce(&mut ValuePool<T>) -> R) -> R {
+        self.key.with(|pool| f(&mut pool.borrow_mut()))
+    }
+
+    /// Same as [`ValuePool<T>::push`] on the pool of the current thread.
+    #[inline]
+    pub fn push(&self, value: T) -> ValueRef<T> {
+        self.with(|pool| pool.push(value))
+    }
+
+    /// Same as [`ValuePool<T>::take`] on the pool of the current thread.
+    #[inline]
+    pub fn take(&self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        self.with(|pool| pool.take(reference))
+    }
+
+    /// Same as [`ValuePool<T>::clear`] on the pool of the current thread.
+    #[inline]
+    pub fn clear(&self) {
+        self.with(|pool| pool.clear())
+    }
+}
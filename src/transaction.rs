@@ -0,0 +1,301 @@
+//! This module implements [`Transaction<T>`] which applies changes to a [`ValuePool<T>`] in an all-or-nothing fashion.
+use crate::{ValuePool, ValueRef};
+use nonmax::NonMaxUsize;
+
+#[derive(Debug)]
+enum Undo<T> {
+    Pushed {
+        index: NonMaxUsize,
+        appended: bool,
+    },
+    Removed {
+        index: NonMaxUsize,
+        value: T,
+        popped: bool,
+    },
+    Replaced {
+        index: NonMaxUsize,
+        old: T,
+    },
+}
+
+/// A [`Transaction<T>`] mutably borrows a [`ValuePool<T>`] and records every change made through it.
+/// Changes are visible right away, but they are only kept if [`Transaction::commit`] is called.
+/// If the [`Transaction<T>`] gets dropped (e.g. by an early return with `?`) or [`Transaction::rollback`] is called,
+/// the [`ValuePool<T>`] is restored to its exact previous state, including the order of its empty positions.
+///
+/// Since [`Transaction::commit`] can't fail, multiple pools can be changed together:
+/// create one [`Transaction`] per pool and only commit them after all steps succeeded.
+/// ```
+/// use value_pool::{ValuePool, ValueRef, transaction::Transaction};
+///
+/// let mut nodes: ValuePool<&str> = ValuePool::new();
+/// let mut edges: ValuePool<(ValueRef<&str>, ValueRef<&str>)> = ValuePool::new();
+/// let a = nodes.push("a");
+///
+/// fn connect_new(
+///     nodes: &mut ValuePool<&'static str>,
+///     edges: &mut ValuePool<(ValueRef<&'static str>, ValueRef<&'static str>)>,
+///     from: ValueRef<&'static str>,
+///     fail: bool,
+/// ) -> Result<(), &'static str> {
+///     let mut nodes_tx = Transaction::new(nodes);
+///     let mut edges_tx = Transaction::new(edges);
+///     let b = nodes_tx.push("b");
+///     if fail {
+///         return Err("something went wrong"); // both transactions roll back
+///     }
+///     edges_tx.push((from, b));
+///     nodes_tx.commit();
+///     edges_tx.commit();
+///     Ok(())
+/// }
+///
+/// assert!(connect_new(&mut nodes, &mut edges, a, true).is_err());
+/// assert_eq!(nodes.element_count(), 1);
+/// assert!(edges.is_empty());
+///
+/// assert!(connect_new(&mut nodes, &mut edges, a, false).is_ok());
+/// assert_eq!(nodes.element_count(), 2);
+/// assert_eq!(edges.element_count(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Transaction<'a, T> {
+    pool: &'a mut ValuePool<T>,
+    undo: Vec<Undo<T>>,
+}
+
+impl<'a, T> Transaction<'a, T> {
+    /// Starts a new [`Transaction`] on `pool`.
+    #[inline]
+    pub fn new(pool: &'a mut ValuePool<T>) -> Transaction<'a, T> {
+        Transaction {
+            pool,
+            undo: (Vec::new()),
+        }
+    }
+
+    /// Runs `f` inside a new [`Transaction`] on `pool`. If `f` returns [`Ok`], the changes are committed, else they are rolled back.
+    /// ```
+    /// use value_pool::{ValuePool, transaction::Transaction};
+    ///
+    /// let mut pool: ValuePool<u32> = ValuePool::new();
+    /// let res: Result<(), ()> = Transaction::run(&mut pool, |tx| {
+    ///     tx.push(1);
+    ///     tx.push(2);
+    ///     Err(())
+    /// });
+    /// assert!(res.is_err());
+    /// assert!(pool.is_empty());
+    /// ```
+    #[inline]
+    pub fn run<R, E>(
+        pool: &'a mut ValuePool<T>,
+        f: impl FnOnce(&mut Transaction<'a, T>) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let mut transaction = Transaction::new(pool);
+        let res = f(&mut transaction)?;
+        transaction.commit();
+        Ok(res)
+    }
+
+    /// Same as [`ValuePool<T>::get`].
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.pool.get(reference)
+    }
+
+    /// Same as [`ValuePool<T>::push`], but the push is undone on rollback.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let appended = self.pool.open_indices.is_empty();
+        let reference = self.pool.push(value);
+        self.undo.push(Undo::Pushed {
+            index: reference.index,
+            appended,
+        });
+        reference
+    }
+
+    /// Same as [`ValuePool<T>::remove`], but the removed value is kept until the [`Transaction`] ends.
+    /// Returns true if a value was removed; pinned values are not removed. Removed values are returned by [`Transaction::commit`].
+    ///
+    /// # Complexity
+    /// `O(log p)` where `p` is the number of pinned positions
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        if !self.pool.has_item(reference) || self.pool.is_pinned(reference) {
+            return false;
+        }
+        let popped = reference.index.get() + 1 == self.pool.store.len();
+        let value = if popped {
+            self.pool.store.pop().flatten()
+        } else {
+            self.pool.take(reference)
+        };
+        if let Some(value) = value {
+            self.undo.push(Undo::Removed {
+                index: reference.index,
+                value,
+                popped,
+            });
+            return true;
+        }
+        false
+    }
+
+    /// Replaces the value at `reference` with `value`. Returns false (and drops `value`) if no value is stored at `reference`.
+    /// The replaced value is returned by [`Transaction::commit`].
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn replace(&mut self, reference: impl Into<ValueRef<T>>, value: T) -> bool {
+        let reference: ValueRef<T> = reference.into();
+        match self.pool.get_mut(reference) {
+            None => false,
+            Some(x) => {
+                let old = std::mem::replace(x, value);
+                self.undo.push(Undo::Replaced {
+                    index: reference.index,
+                    old,
+                });
+                true
+            }
+        }
+    }
+
+    /// Keeps all changes and returns the removed and replaced values together with their (former) position.
+    #[inline]
+    pub fn commit(mut self) -> Vec<(ValueRef<T>, T)> {
+        std::mem::take(&mut self.undo)
+            .into_iter()
+            .filter_map(|undo| match undo {
+                Undo::Pushed { .. } => None,
+                Undo::Removed { index, value, .. } | Undo::Replaced { index, old: value } => {
+                    Some((ValueRef::new_nonmax(index), value))
+                }
+            })
+            .collect()
+    }
+
+    /// Undoes all changes. Equivalent to dropping this [`Transaction`].
+    #[inline]
+    pub fn rollback(self) {}
+
+    fn undo_all(&mut self) {
+        while let Some(undo) = self.undo.pop() {
+            match undo {
+                Undo::Pushed { index, appended } => {
+                    if appended {
+                        self.pool.store.pop();
+                    } else {
+                        self.pool.store[index.get()] = None;
+                        // the reuse stays counted, refs handed out inside the transaction are stale
+                        self.pool.open_indices.push(index);
+                    }
+                }
+                Undo::Removed {
+                    index,
+                    value,
+                    popped,
+                } => {
+                    if popped {
+                        self.pool.store.push(Some(value));
+                    } else {
+                        // `take` pushed `index` onto `open_indices`; all later changes are already undone
+                        self.pool.open_indices.pop();
+                        self.pool.store[index.get()] = Some(value);
+                    }
+                }
+                Undo::Replaced { index, old } => {
+                    self.pool.store[index.get()] = Some(old);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for Transaction<'_, T> {
+    fn drop(&mut self) {
+        self.undo_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transaction;
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_rollback_restores_exact_state() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        for i in 0..6 {
+            pool.push(i);
+        }
+        pool.take(ValueRef::new(1));
+        pool.take(ValueRef::new(3));
+        // everything but the reuse counts, which never go down
+        let state = |pool: &ValuePool<u32>| {
+            (
+                pool.store.clone(),
+                pool.open_indices.clone(),
+                pool.pinned.clone(),
+            )
+        };
+        let before = state(&pool);
+
+        let mut tx = Transaction::new(&mut pool);
+        tx.push(10); // reuses 3
+        tx.push(11); // reuses 1
+        tx.push(12); // appended
+        assert!(tx.remove(ValueRef::new(6))); // last one
+        assert!(tx.remove(ValueRef::new(0)));
+        assert!(tx.replace(ValueRef::new(2), 20));
+        assert!(!tx.remove(ValueRef::new(0)));
+        tx.rollback();
+
+        assert_eq!(state(&pool), before);
+        #[cfg(feature = "slot-stats")]
+        assert_eq!(
+            (1..=6)
+                .map(|index| pool.slot_reuse_count(ValueRef::new(index)))
+                .collect::<Vec<_>>(),
+            vec![1, 0, 1, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_commit_returns_removed_values() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        let one = pool.push(1);
+        let two = pool.push(2);
+
+        let mut tx = Transaction::new(&mut pool);
+        tx.remove(one);
+        tx.replace(two, 22);
+        assert_eq!(tx.commit(), vec![(one, 1), (two, 2)]);
+        assert_eq!(pool.get(two), Some(&22));
+        assert_eq!(pool.get(one), None);
+    }
+
+    #[test]
+    fn test_pinned_items_stay() {
+        let mut pool: ValuePool<u32> = ValuePool::new();
+        let first = pool.push(1);
+        let last = pool.push(2);
+        pool.pin_slot(first);
+        pool.pin_slot(last);
+
+        let mut tx = Transaction::new(&mut pool);
+        assert!(!tx.remove(last));
+        assert!(!tx.remove(first));
+        assert!(tx.commit().is_empty());
+        assert_eq!(pool.get(last), Some(&2));
+        assert_eq!(pool.element_count(), 2);
+    }
+}
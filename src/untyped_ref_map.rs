@@ -0,0 +1,203 @@
+//! This module implements [`UntypedRefMap<V>`], a map from [`UntypedValueRef`]s of several pools to metadata of type `V`.
+use crate::UntypedValueRef;
+
+/// Identifies the pool an [`UntypedValueRef`] belongs to inside an [`UntypedRefMap<V>`].
+/// Returned by [`UntypedRefMap::register_namespace`]; only use it with the map that returned it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Namespace(u32);
+
+/// An [`UntypedRefMap<V>`] stores a `V` for [`UntypedValueRef`]s of different pools (and types).
+/// Refs of different pools can share a position, so every key is tagged with the [`Namespace`] of its pool.
+/// Each namespace is a dense vector indexed by position, which makes lookups `O(1)` and fast.
+/// ```
+/// use value_pool::{ValuePool, untyped_ref_map::UntypedRefMap};
+///
+/// let mut meshes: ValuePool<&str> = ValuePool::new();
+/// let mut sounds: ValuePool<u32> = ValuePool::new();
+/// let mut labels: UntypedRefMap<&str> = UntypedRefMap::new();
+/// let mesh_space = labels.register_namespace();
+/// let sound_space = labels.register_namespace();
+///
+/// let cube = meshes.push("cube");
+/// let beep = sounds.push(440);
+/// // both refs point to position 0, but don't collide
+/// labels.insert(mesh_space, cube, "a cube");
+/// labels.insert(sound_space, beep, "a beep");
+///
+/// assert_eq!(labels.get(mesh_space, cube), Some(&"a cube"));
+/// assert_eq!(labels.get(sound_space, beep), Some(&"a beep"));
+/// assert_eq!(labels.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UntypedRefMap<V> {
+    namespaces: Vec<Vec<Option<V>>>,
+    len: usize,
+}
+
+impl<V> Default for UntypedRefMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> UntypedRefMap<V> {
+    /// Creates a new, empty [`UntypedRefMap`] without namespaces.
+    #[inline]
+    pub const fn new() -> UntypedRefMap<V> {
+        UntypedRefMap {
+            namespaces: (Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Registers a new [`Namespace`], usually one per pool.
+    ///
+    /// # Panic
+    /// Panics if more than [`u32::MAX`] namespaces get registered.
+    #[inline]
+    pub fn register_namespace(&mut self) -> Namespace {
+        let namespace =
+            Namespace(u32::try_from(self.namespaces.len()).expect("Less than u32::MAX namespaces"));
+        self.namespaces.push(Vec::new());
+        namespace
+    }
+
+    /// Returns the number of stored values across all namespaces.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no values are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value` for `key` in `namespace` and returns the value stored before.
+    ///
+    /// # Panic
+    /// Panics if `namespace` wasn't registered in this map.
+    ///
+    /// # Complexity
+    /// `O(1)` (amortized)
+    #[inline]
+    pub fn insert(
+        &mut self,
+        namespace: Namespace,
+        key: impl Into<UntypedValueRef>,
+        value: V,
+    ) -> Option<V> {
+        let index = key.into().index.get();
+        let slots = self
+            .namespaces
+            .get_mut(namespace.0 as usize)
+            .expect("Namespace to be registered in this map");
+        if index >= slots.len() {
+            slots.resize_with(index + 1, || None);
+        }
+        let old = slots[index].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Gets a borrow of the value for `key` in `namespace`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get(&self, namespace: Namespace, key: impl Into<UntypedValueRef>) -> Option<&V> {
+        self.namespaces
+            .get(namespace.0 as usize)?
+            .get(key.into().index.get())?
+            .as_ref()
+    }
+
+    /// Gets a mut borrow of the value for `key` in `namespace`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn get_mut(
+        &mut self,
+        namespace: Namespace,
+        key: impl Into<UntypedValueRef>,
+    ) -> Option<&mut V> {
+        self.namespaces
+            .get_mut(namespace.0 as usize)?
+            .get_mut(key.into().index.get())?
+            .as_mut()
+    }
+
+    /// Returns true if a value is stored for `key` in `namespace`.
+    #[inline]
+    pub fn contains_key(&self, namespace: Namespace, key: impl Into<UntypedValueRef>) -> bool {
+        self.get(namespace, key).is_some()
+    }
+
+    /// Removes and returns the value for `key` in `namespace`.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn remove(&mut self, namespace: Namespace, key: impl Into<UntypedValueRef>) -> Option<V> {
+        let value = self
+            .namespaces
+            .get_mut(namespace.0 as usize)?
+            .get_mut(key.into().index.get())?
+            .take()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes all values of `namespace`, e.g. after its pool got cleared. The namespace stays registered.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the greatest position stored in `namespace`
+    #[inline]
+    pub fn clear_namespace(&mut self, namespace: Namespace) {
+        if let Some(slots) = self.namespaces.get_mut(namespace.0 as usize) {
+            self.len -= slots.iter().filter(|slot| slot.is_some()).count();
+            slots.clear();
+        }
+    }
+
+    /// Iterates over all values of `namespace` in slot order.
+    #[inline]
+    pub fn iter(&self, namespace: Namespace) -> impl Iterator<Item = (UntypedValueRef, &V)> {
+        self.namespaces
+            .get(namespace.0 as usize)
+            .into_iter()
+            .flat_map(|slots| slots.iter().enumerate())
+            .filter_map(|(index, value)| Some((UntypedValueRef::new(index), value.as_ref()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UntypedRefMap;
+    use crate::UntypedValueRef;
+
+    #[test]
+    fn test_namespaces_are_separate() {
+        let mut map: UntypedRefMap<u32> = UntypedRefMap::new();
+        let first = map.register_namespace();
+        let second = map.register_namespace();
+        let key = UntypedValueRef::new(3);
+
+        assert_eq!(map.insert(first, key, 1), None);
+        assert_eq!(map.insert(first, key, 2), Some(1));
+        map.insert(second, key, 3);
+        map.insert(second, UntypedValueRef::new(0), 4);
+        assert_eq!(map.len(), 3);
+
+        map.clear_namespace(second);
+        assert_eq!(map.len(), 1);
+        assert!(!map.contains_key(second, key));
+        assert_eq!(map.iter(first).collect::<Vec<_>>(), vec![(key, &2)]);
+        assert_eq!(map.remove(first, key), Some(2));
+        assert!(map.is_empty());
+    }
+}
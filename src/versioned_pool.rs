@@ -0,0 +1,192 @@
+//! This module implements [`VersionedPool<T>`], a [`ValuePool<T>`] that stamps every position with the version it was last changed in.
+use crate::{ValuePool, ValueRef};
+
+/// A [`VersionedPool<T>`] keeps a version counter that is incremented by every mutation.
+/// Each position remembers the version it was last changed in, so [`VersionedPool::iter_modified_since`]
+/// can answer which positions changed since an earlier [`VersionedPool::version`].
+/// ```
+/// use value_pool::versioned_pool::VersionedPool;
+///
+/// let mut pool: VersionedPool<&str> = VersionedPool::new();
+/// let a = pool.push("a");
+/// let b = pool.push("b");
+/// let last_frame = pool.version();
+///
+/// pool.replace(b, "B");
+/// pool.take(a);
+///
+/// let changed: Vec<_> = pool.iter_modified_since(last_frame).collect();
+/// assert_eq!(changed, vec![(a, None), (b, Some(&"B"))]);
+/// assert_eq!(pool.iter_modified_since(pool.version()).count(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VersionedPool<T> {
+    pool: ValuePool<T>,
+    // version each position was last changed in, by index
+    versions: Vec<u64>,
+    version: u64,
+}
+
+impl<T> Default for VersionedPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> VersionedPool<T> {
+    /// Creates a new, empty [`VersionedPool`] at version `0`.
+    #[inline]
+    pub const fn new() -> VersionedPool<T> {
+        VersionedPool {
+            pool: (ValuePool::new()),
+            versions: (Vec::new()),
+            version: 0,
+        }
+    }
+
+    /// Gives read-only access to the underlying [`ValuePool`].
+    #[inline]
+    pub fn as_pool(&self) -> &ValuePool<T> {
+        &self.pool
+    }
+
+    /// Returns the number of elements stored.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.pool.element_count()
+    }
+
+    /// Returns true if no elements are stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Returns the current version, which is the number of mutations so far.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the version the position `reference` was last changed in, or [`None`] if it was never used.
+    #[inline]
+    pub fn slot_version(&self, reference: impl Into<ValueRef<T>>) -> Option<u64> {
+        let reference: ValueRef<T> = reference.into();
+        self.versions.get(reference.index.get()).copied()
+    }
+
+    #[inline]
+    fn bump(&mut self, reference: ValueRef<T>) {
+        self.version += 1;
+        let index = reference.index.get();
+        if index >= self.versions.len() {
+            self.versions.resize(index + 1, 0);
+        }
+        self.versions[index] = self.version;
+    }
+
+    /// Iterates over all positions changed after `version` in slot order, with their current value.
+    /// A position whose value got taken or removed has the value [`None`].
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the number of positions ever used
+    #[inline]
+    pub fn iter_modified_since(
+        &self,
+        version: u64,
+    ) -> impl Iterator<Item = (ValueRef<T>, Option<&T>)> {
+        self.versions
+            .iter()
+            .enumerate()
+            .filter(move |(_, changed)| **changed > version)
+            .map(|(index, _)| {
+                let reference = ValueRef::new(index);
+                (reference, self.pool.get(reference))
+            })
+    }
+
+    /// Same as [`ValuePool::get`].
+    #[inline]
+    pub fn get(&self, reference: impl Into<ValueRef<T>>) -> Option<&T> {
+        self.pool.get(reference)
+    }
+
+    /// Same as [`ValuePool::push`].
+    #[inline]
+    pub fn push(&mut self, value: T) -> ValueRef<T> {
+        let reference = self.pool.push(value);
+        self.bump(reference);
+        reference
+    }
+
+    /// Calls `f` with a mut borrow of the value at `reference` and stamps the position.
+    /// Returns [`None`] if no value is stored there.
+    #[inline]
+    pub fn update<R>(
+        &mut self,
+        reference: impl Into<ValueRef<T>>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let reference: ValueRef<T> = reference.into();
+        let result = f(self.pool.get_mut(reference)?);
+        self.bump(reference);
+        Some(result)
+    }
+
+    /// Replaces the value at `reference` with `value` and returns the old one.
+    /// Returns `Err(value)` if no value is stored there.
+    #[inline]
+    pub fn replace(&mut self, reference: impl Into<ValueRef<T>>, value: T) -> Result<T, T> {
+        let reference: ValueRef<T> = reference.into();
+        match self.pool.get_mut(reference) {
+            Some(stored) => {
+                let old = std::mem::replace(stored, value);
+                self.bump(reference);
+                Ok(old)
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Same as [`ValuePool::take`].
+    #[inline]
+    pub fn take(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference: ValueRef<T> = reference.into();
+        let value = self.pool.take(reference)?;
+        self.bump(reference);
+        Some(value)
+    }
+
+    /// Removes the value at `reference` and returns it, same as [`VersionedPool::take`].
+    #[inline]
+    pub fn remove(&mut self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        self.take(reference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionedPool;
+
+    #[test]
+    fn test_versions() {
+        let mut pool: VersionedPool<u32> = VersionedPool::new();
+        let first = pool.push(1);
+        let second = pool.push(2);
+        assert_eq!(pool.slot_version(second), Some(2));
+
+        // failed mutations don't count
+        assert_eq!(pool.remove(first), Some(1));
+        assert_eq!(pool.remove(first), None);
+        assert_eq!(pool.update(first, |v| *v += 1), None);
+        assert_eq!(pool.replace(first, 4), Err(4));
+        assert_eq!(pool.version(), 3);
+
+        assert_eq!(pool.update(second, |v| *v * 3), Some(6));
+        assert_eq!(
+            pool.iter_modified_since(3).collect::<Vec<_>>(),
+            vec![(second, Some(&2))]
+        );
+        assert_eq!(pool.iter_modified_since(0).count(), 2);
+    }
+}
@@ -0,0 +1,321 @@
+//! This module implements [`WaitablePool<T>`], a thread safe pool whose positions can be reserved first and filled later.
+//! Consumers can block until a value arrives, which gives every position oneshot channel semantics.
+use std::{
+    sync::{Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::{ValuePool, ValueRef};
+
+/// A [`WaitablePool<T>`] is a [`ValuePool<T>`] behind a [`Mutex`] whose positions can be *reserved*:
+/// A reserved position has no value yet, but [`WaitablePool::take_or_wait`] blocks until a producer [fills](WaitablePool::fill) it.
+/// Share it between threads with an [`Arc`](std::sync::Arc) or scoped threads.
+/// ```
+/// use value_pool::waitable_pool::WaitablePool;
+///
+/// let pool: WaitablePool<String> = WaitablePool::new();
+/// let reference = pool.reserve();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         std::thread::sleep(std::time::Duration::from_millis(10));
+///         pool.fill(reference, "done".to_string()).unwrap();
+///     });
+///     assert_eq!(pool.take_or_wait(reference), Some("done".to_string()));
+/// });
+/// assert!(pool.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct WaitablePool<T> {
+    state: Mutex<State<T>>,
+    filled: Condvar,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    // `Some(None)` marks a reserved position
+    pool: ValuePool<Option<T>>,
+    // how often each position got vacated (taken or canceled), by index; waiters give up once it changes
+    generations: Vec<u64>,
+}
+
+impl<T> State<T> {
+    #[inline]
+    fn generation(&self, reference: ValueRef<Option<T>>) -> u64 {
+        self.generations
+            .get(reference.index.get())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Takes whatever is stored at `reference` and starts a new generation of it.
+    #[inline]
+    fn vacate(&mut self, reference: ValueRef<Option<T>>) -> Option<Option<T>> {
+        let taken = self.pool.take(reference)?;
+        let index = reference.index.get();
+        if index >= self.generations.len() {
+            self.generations.resize(index + 1, 0);
+        }
+        self.generations[index] += 1;
+        Some(taken)
+    }
+
+    /// Returns true while `reference` is reserved and wasn't vacated since `generation` got read.
+    #[inline]
+    fn still_reserved(&self, reference: ValueRef<Option<T>>, generation: u64) -> bool {
+        self.generation(reference) == generation && matches!(self.pool.get(reference), Some(None))
+    }
+
+    /// Takes the value at `reference`, unless the position got vacated since `generation` got read.
+    #[inline]
+    fn take_if_same_generation(
+        &mut self,
+        reference: ValueRef<Option<T>>,
+        generation: u64,
+    ) -> Option<T> {
+        if self.generation(reference) != generation {
+            return None;
+        }
+        self.pool.get(reference)?.as_ref()?;
+        self.vacate(reference).flatten()
+    }
+}
+
+impl<T> Default for WaitablePool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn typed<T>(reference: ValueRef<Option<T>>) -> ValueRef<T> {
+    ValueRef::new_nonmax(reference.index)
+}
+
+#[inline]
+fn slot<T>(reference: ValueRef<T>) -> ValueRef<Option<T>> {
+    ValueRef::new_nonmax(reference.index)
+}
+
+impl<T> WaitablePool<T> {
+    /// Creates a new, empty [`WaitablePool`].
+    #[inline]
+    pub const fn new() -> WaitablePool<T> {
+        WaitablePool {
+            state: (Mutex::new(State {
+                pool: (ValuePool::new()),
+                generations: (Vec::new()),
+            })),
+            filled: (Condvar::new()),
+        }
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the number of filled and reserved positions.
+    #[inline]
+    pub fn element_count(&self) -> usize {
+        self.lock().pool.element_count()
+    }
+
+    /// Returns true if no position is filled or reserved.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().pool.is_empty()
+    }
+
+    /// Pushes `value` and returns its position.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn push(&self, value: T) -> ValueRef<T> {
+        typed(self.lock().pool.push(Some(value)))
+    }
+
+    /// Reserves a position without a value. Fill it with [`WaitablePool::fill`] or release it with [`WaitablePool::cancel`].
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn reserve(&self) -> ValueRef<T> {
+        typed(self.lock().pool.push(None))
+    }
+
+    /// Stores `value` at the reserved position `reference` and wakes up threads waiting for it.
+    /// Returns `Err(value)` if `reference` isn't reserved.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn fill(&self, reference: impl Into<ValueRef<T>>, value: T) -> Result<(), T> {
+        let mut state = self.lock();
+        match state.pool.get_mut(slot(reference.into())) {
+            Some(reserved @ None) => {
+                *reserved = Some(value);
+                drop(state);
+                self.filled.notify_all();
+                Ok(())
+            }
+            _ => Err(value),
+        }
+    }
+
+    /// Releases the reserved position `reference`. Threads waiting for it return [`None`],
+    /// even if the position gets reserved or filled again before they wake up.
+    /// Returns false if `reference` isn't reserved.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn cancel(&self, reference: impl Into<ValueRef<T>>) -> bool {
+        let reference = slot(reference.into());
+        let mut state = self.lock();
+        if !matches!(state.pool.get(reference), Some(None)) {
+            return false;
+        }
+        state.vacate(reference);
+        drop(state);
+        self.filled.notify_all();
+        true
+    }
+
+    /// Takes the value at `reference` without waiting. A reserved position stays reserved.
+    ///
+    /// # Complexity
+    /// `O(1)`
+    #[inline]
+    pub fn take(&self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference = slot(reference.into());
+        let mut state = self.lock();
+        state.pool.get(reference)?.as_ref()?;
+        state.vacate(reference).flatten()
+    }
+
+    /// Takes the value at `reference`. If the position is reserved, this blocks until it gets filled or canceled.
+    /// Returns [`None`] if neither a value is stored nor the position is reserved,
+    /// or if another thread took the value first, even if the position got reused in the meantime.
+    #[inline]
+    pub fn take_or_wait(&self, reference: impl Into<ValueRef<T>>) -> Option<T> {
+        let reference = slot(reference.into());
+        let state = self.lock();
+        let generation = state.generation(reference);
+        let mut state = self
+            .filled
+            .wait_while(state, |state| state.still_reserved(reference, generation))
+            .unwrap_or_else(|e| e.into_inner());
+        state.take_if_same_generation(reference, generation)
+    }
+
+    /// Same as [`WaitablePool::take_or_wait`], but gives up after `timeout`. The position stays reserved in that case.
+    #[inline]
+    pub fn take_or_wait_timeout(
+        &self,
+        reference: impl Into<ValueRef<T>>,
+        timeout: Duration,
+    ) -> Option<T> {
+        let reference = slot(reference.into());
+        let deadline = Instant::now() + timeout;
+        let mut state = self.lock();
+        let generation = state.generation(reference);
+        while state.still_reserved(reference, generation) {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self
+                .filled
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        state.take_if_same_generation(reference, generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WaitablePool;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reserved_positions() {
+        let pool: WaitablePool<u32> = WaitablePool::new();
+        let reserved = pool.reserve();
+        let filled = pool.push(3);
+
+        assert_eq!(pool.take(reserved), None);
+        assert_eq!(pool.fill(filled, 4), Err(4));
+        assert_eq!(
+            pool.take_or_wait_timeout(reserved, Duration::from_millis(1)),
+            None
+        );
+        assert_eq!(pool.element_count(), 2);
+
+        assert!(pool.cancel(reserved));
+        assert!(!pool.cancel(reserved));
+        assert_eq!(pool.take_or_wait(reserved), None);
+        assert_eq!(pool.take_or_wait(filled), Some(3));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_cancel_wakes_waiter_before_reuse() {
+        let pool: WaitablePool<u32> = WaitablePool::new();
+        let reserved = pool.reserve();
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| pool.take_or_wait(reserved));
+            std::thread::sleep(Duration::from_millis(50));
+            // the position gets reused before the waiter can look at it again
+            assert!(pool.cancel(reserved));
+            assert_eq!(pool.reserve(), reserved);
+            pool.fill(reserved, 7).unwrap();
+            assert_eq!(waiter.join().unwrap(), None);
+        });
+        assert_eq!(pool.take(reserved), Some(7));
+
+        // the same interleaving, without relying on timing
+        let reserved = pool.reserve();
+        let generation = pool.lock().generation(super::slot(reserved));
+        assert!(pool.cancel(reserved));
+        assert_eq!(pool.reserve(), reserved);
+        let reserved = super::slot(reserved);
+        let mut state = pool.lock();
+        assert!(!state.still_reserved(reserved, generation));
+        assert_eq!(state.take_if_same_generation(reserved, generation), None);
+    }
+
+    #[test]
+    fn test_waiter_ignores_value_after_take_and_reuse() {
+        let pool: WaitablePool<u32> = WaitablePool::new();
+        let reserved = pool.reserve();
+        let generation = pool.lock().generation(super::slot(reserved));
+        // the value the waiter waited for is taken by someone else, then the position gets reused
+        pool.fill(reserved, 1).unwrap();
+        assert_eq!(pool.take(reserved), Some(1));
+        assert_eq!(pool.push(2), reserved);
+        {
+            let reserved = super::slot(reserved);
+            let mut state = pool.lock();
+            assert!(!state.still_reserved(reserved, generation));
+            assert_eq!(state.take_if_same_generation(reserved, generation), None);
+        }
+        assert_eq!(pool.take_or_wait(reserved), Some(2));
+
+        // the same with threads
+        let reserved = pool.reserve();
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| pool.take_or_wait(reserved));
+            std::thread::sleep(Duration::from_millis(50));
+            // holding the lock, so the waiter only wakes up after the position got reused
+            let mut state = pool.lock();
+            *state.pool.get_mut(super::slot(reserved)).unwrap() = Some(3);
+            assert_eq!(state.vacate(super::slot(reserved)), Some(Some(3)));
+            assert_eq!(state.pool.push(Some(4)), super::slot(reserved));
+            drop(state);
+            pool.filled.notify_all();
+            assert_eq!(waiter.join().unwrap(), None);
+        });
+        assert_eq!(pool.take(reserved), Some(4));
+    }
+}
@@ -0,0 +1,93 @@
+//! This module implements helpers for parallel pools, where one [`ValuePool`] mirrors the positions of another one.
+use crate::{ValuePool, ValueRef};
+
+impl<T> ValuePool<T> {
+    /// Iterates over all positions occupied in both `self` and `other`, in slot order.
+    /// This is meant for parallel pools, where the item at a position of `other` belongs to the item at the same position of `self`
+    /// (e.g. positions in one pool and velocities in the other). The returned [`ValueRef<T>`] can be converted for `other`
+    /// with [`UntypedValueRef`](crate::UntypedValueRef).
+    /// ```
+    /// use value_pool::{UntypedValueRef, ValuePool};
+    ///
+    /// let mut names: ValuePool<&str> = ValuePool::new();
+    /// let mut ages: ValuePool<u32> = ValuePool::new();
+    /// for (name, age) in [("alice", 31), ("bob", 45), ("carol", 27)] {
+    ///     names.push(name);
+    ///     ages.push(age);
+    /// }
+    /// let carol = names.find(&"carol").unwrap();
+    /// let bob: UntypedValueRef = names.find(&"bob").unwrap().into();
+    /// ages.take(bob); // bob's age is unknown
+    ///
+    /// let pairs: Vec<(&str, u32)> = names.zip_occupied(&ages).map(|(_, name, age)| (*name, *age)).collect();
+    /// assert_eq!(pairs, vec![("alice", 31), ("carol", 27)]);
+    /// assert_eq!(names.zip_occupied(&ages).last().map(|(reference, _, _)| reference), Some(carol));
+    /// ```
+    ///
+    /// # Complexity
+    /// `O(min(n, m))` where `n` and `m` are the lengths of the underlying stores
+    #[inline]
+    pub fn zip_occupied<'a, B>(
+        &'a self,
+        other: &'a ValuePool<B>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &'a T, &'a B)> {
+        self.store
+            .iter()
+            .zip(other.store.iter())
+            .enumerate()
+            .filter_map(|(index, (a, b))| Some((ValueRef::new(index), a.as_ref()?, b.as_ref()?)))
+    }
+
+    /// Same as [`ValuePool::zip_occupied`], but gives mutable access to the items of `self`.
+    ///
+    /// # Complexity
+    /// `O(min(n, m))` where `n` and `m` are the lengths of the underlying stores
+    #[inline]
+    pub fn zip_occupied_mut<'a, B>(
+        &'a mut self,
+        other: &'a ValuePool<B>,
+    ) -> impl Iterator<Item = (ValueRef<T>, &'a mut T, &'a B)> {
+        self.store
+            .iter_mut()
+            .zip(other.store.iter())
+            .enumerate()
+            .filter_map(|(index, (a, b))| Some((ValueRef::new(index), a.as_mut()?, b.as_ref()?)))
+    }
+
+    /// Returns true if every position occupied in `self` is occupied in `other` too,
+    /// e.g. to check that a parallel pool wasn't forgotten when pushing.
+    ///
+    /// # Complexity
+    /// `O(n)` where `n` is the length of the underlying store
+    #[inline]
+    pub fn is_occupancy_subset_of<B>(&self, other: &ValuePool<B>) -> bool {
+        self.store.iter().enumerate().all(|(index, slot)| {
+            slot.is_none() || other.store.get(index).is_some_and(Option::is_some)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValuePool, ValueRef};
+
+    #[test]
+    fn test_parallel_pools() {
+        let mut healths: ValuePool<u32> = ValuePool::new();
+        let mut regeneration: ValuePool<u32> = ValuePool::new();
+        for (health, regen) in [(10, 1), (20, 2), (30, 3)] {
+            healths.push(health);
+            regeneration.push(regen);
+        }
+        regeneration.take(ValueRef::new(1));
+        assert!(regeneration.is_occupancy_subset_of(&healths));
+        assert!(!healths.is_occupancy_subset_of(&regeneration));
+
+        for (_, health, regen) in healths.zip_occupied_mut(&regeneration) {
+            *health += regen;
+        }
+        let values: Vec<u32> = healths.zip_occupied(&healths).map(|(_, a, _)| *a).collect();
+        assert_eq!(values, vec![11, 20, 33]);
+        assert!(ValuePool::<u32>::new().is_occupancy_subset_of(&regeneration));
+    }
+}